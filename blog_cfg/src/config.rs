@@ -1,6 +1,7 @@
 //! Configuration file management for the Blog Builder.
 
 use std::{
+    collections::HashMap,
     fmt,
     fs,
     path::{
@@ -9,11 +10,22 @@ use std::{
     },
 };
 
-use serde::Deserialize;
+use serde::{
+    de::Error,
+    Deserialize,
+    Deserializer,
+};
 
-use blog_err::BlogResult;
+use blog_err::{
+    BlogError,
+    BlogErrorLocation,
+    BlogResult,
+};
 
-use blog_env::CONFIG_FILE_NAME;
+use blog_env::{
+    BUILTIN_CONTROLS,
+    CONFIG_FILE_NAME,
+};
 
 #[derive(Clone, Default, Deserialize, Debug)]
 /// A configuration file that dictates Blog Builder settings.
@@ -33,6 +45,13 @@ pub struct Config {
 
     /// Media information.
     pub media: Option<MediaConfig>,
+
+    /// Control sequences defined by the site, by name.
+    #[serde(default, deserialize_with = "controls")]
+    pub controls: Option<HashMap<String, ControlConfig>>,
+
+    /// Math information.
+    pub math: Option<MathConfig>,
 }
 
 #[derive(Clone, Default, Deserialize, Debug)]
@@ -46,6 +65,15 @@ pub struct SiteConfig {
 
     /// Site style (for CSS source).
     pub style: Option<SiteStyle>,
+
+    /// Note style (for footnotes).
+    pub notes: Option<NoteStyle>,
+
+    /// Allow raw HTML blocks (defaults to true).
+    pub raw_html: Option<bool>,
+
+    /// Display a permalink beside each header on hover (defaults to false).
+    pub permalinks: Option<bool>,
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -76,6 +104,20 @@ impl fmt::Display for SiteStyle {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+/// Note style options.
+pub enum NoteStyle {
+    /// Numbered notes at the end of the page.
+    Footnotes,
+
+    /// Notes in the margin next to their references.
+    /// 
+    /// Only the book and linotype styles have room for sidenotes;
+    /// other styles fall back to footnotes.
+    Sidenotes,
+}
+
 #[derive(Clone, Deserialize, Debug)]
 /// Configuration information for site menu.
 pub struct MenuConfig {
@@ -101,6 +143,121 @@ pub struct MediaConfig {
     pub include: Vec<String>,
 }
 
+#[derive(Clone, Default, Deserialize, Debug)]
+/// Configuration information for math blocks.
+pub struct MathConfig {
+    /// Numbering of math blocks (defaults to numbering by type).
+    pub numbering: Option<Numbering>,
+
+    /// Types of math block that are not numbered (defaults to proofs).
+    pub unnumbered: Option<Vec<String>>,
+
+    /// Rendering of equations (defaults to MathJax).
+    pub render: Option<MathRender>,
+}
+
+impl MathConfig {
+    /// Check if math blocks of a given type are numbered.
+    /// 
+    /// # Parameters
+    /// - `blocktype` (`&str`): the type of math block
+    /// 
+    /// # Returns
+    /// A `bool` indicating if blocks of this type are numbered.
+    pub fn numbered(&self, blocktype: &str) -> bool {
+        if self.numbering == Some (Numbering::Unnumbered) {
+            return false;
+        }
+
+        match &self.unnumbered {
+            Some (u) => !u.iter().any(|t| t.eq_ignore_ascii_case(blocktype)),
+            None => !blocktype.eq_ignore_ascii_case("proof"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+/// Numbering options for math blocks.
+pub enum Numbering {
+    /// Each type of block numbered through the page (Theorem 3).
+    Type,
+
+    /// Each type of block numbered within each section (Theorem 2.3).
+    Section,
+
+    /// No numbers.
+    #[serde(rename = "none")]
+    Unnumbered,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+/// Rendering options for equations.
+pub enum MathRender {
+    /// Equations rendered in the browser by MathJax.
+    Mathjax,
+
+    /// Equations rendered to MathML when the site is built, with
+    /// MathJax only for equations that cannot be rendered.
+    Mathml,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+/// Configuration information for a control sequence defined by the site.
+pub struct ControlConfig {
+    /// Arguments to the control sequence.
+    pub args: ControlArgs,
+
+    /// HTML containing placeholders for the arguments.
+    pub html: String,
+
+    /// Parse a body, up to `::end`, in place of `{body}` (defaults to false).
+    pub block: Option<bool>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(untagged)]
+/// Arguments to a control sequence defined by the site.
+pub enum ControlArgs {
+    /// Number of positional arguments.
+    Count (usize),
+
+    /// Name of each argument.
+    Names (Vec<String>),
+}
+
+#[derive(PartialEq, Eq, Hash)]
+/// Name of a control sequence defined by the site.
+struct ControlName (String);
+
+impl<'de> Deserialize<'de> for ControlName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        // Control sequences built into the Blog Builder cannot be redefined
+        if BUILTIN_CONTROLS.contains(&name.as_str()) {
+            return Err (D::Error::custom(format!("control sequence '{}' is built in and cannot be redefined", name)));
+        }
+
+        Ok (Self (name))
+    }
+}
+
+/// Deserialize the control sequences defined by the site.
+/// 
+/// # Parameters
+/// - `deserializer` (`D`): the deserializer
+/// 
+/// # Returns
+/// A `Result` containing the control sequences by name, or an error
+/// naming any control sequence that is built into the Blog Builder.
+fn controls<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<HashMap<String, ControlConfig>>, D::Error> {
+    let controls = HashMap::<ControlName, ControlConfig>::deserialize(deserializer)?;
+
+    Ok (Some (controls.into_iter().map(|(n, c)| (n.0, c)).collect()))
+}
+
 impl Config {
     /// Get information from the site configuration file.
     ///
@@ -125,9 +282,34 @@ impl Config {
         // Parse the TOML into a configuration structure
         let config = match toml::from_str(&toml) {
             Ok (cfg) => cfg,
-            Err (e) => return result.err(e),
+            Err (e) => {
+                // Find the location of the error
+                let location = e.span()
+                    .map(|s| BlogErrorLocation::from_offsets(&toml, s.start, s.end))
+                    .unwrap_or_default();
+
+                return result.err(BlogError::ConfigError {
+                    message: e.message().to_string(),
+                    excerpt: location.excerpt(&toml),
+                    location,
+                });
+            },
         };
 
         result.ok(config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn builtin_control_cannot_be_redefined() {
+        let toml = "[site]\nname = \"Site\"\n\n[controls.image]\nargs = 1\nhtml = \"{1}\"\n";
+
+        let error = toml::from_str::<Config>(toml).unwrap_err();
+
+        assert!(error.message().contains("'image'"));
+    }
+}
@@ -0,0 +1,144 @@
+//! Per-page front matter for the Blog Builder.
+
+use std::path::Path;
+
+use serde::{
+    Deserialize,
+    Deserializer,
+    de::Error,
+};
+
+use blog_err::{
+    BlogError,
+    BlogErrorLocation,
+    BlogResult,
+};
+
+use blog_env::FRONT_MATTER_DELIMITER;
+
+#[derive(Clone, Default, Deserialize, Debug)]
+/// Front matter that dictates settings for a single page.
+/// 
+/// This data is stored at the top of a source file in TOML,
+/// between two lines containing only `FRONT_MATTER_DELIMITER`.
+pub struct FrontMatter {
+    /// Page title (overrides the title derived from the filename).
+    pub title: Option<String>,
+
+    /// Page description.
+    pub description: Option<String>,
+
+    /// Publication date.
+    #[serde(default, deserialize_with = "date")]
+    pub date: Option<String>,
+
+    /// Page author.
+    pub author: Option<String>,
+
+    /// Page tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Is this page a draft?  Drafts are not built.
+    #[serde(default)]
+    pub draft: bool,
+
+    /// Name of the template used to display this page.
+    pub template: Option<String>,
+
+    /// Any other keys, for use by templates.
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+impl FrontMatter {
+    /// Split the front matter off of a source file.
+    /// 
+    /// # Parameters
+    /// - `source` (`&str`): the source code
+    /// - `filename` (`&Path`): the path of the source file, for
+    ///   error messages
+    /// 
+    /// # Returns
+    /// A `BlogResult` containing the front matter (or the default, if
+    /// the source has none), the rest of the source, and the line
+    /// number on which the rest of the source begins.
+    pub fn split<'a>(source: &'a str, filename: &Path) -> BlogResult<(FrontMatter, &'a str, usize)> {
+        // Construct new result
+        let result = BlogResult::default();
+
+        // Front matter must begin on the first line
+        let mut lines = source.split_inclusive('\n');
+        let start = match lines.next() {
+            Some (l) if l.trim_end() == FRONT_MATTER_DELIMITER => l.len(),
+            _ => return result.ok((FrontMatter::default(), source, 1)),
+        };
+
+        // Find the closing delimiter
+        let mut end = start;
+        let mut line = 2;
+        let closed = loop {
+            match lines.next() {
+                Some (l) if l.trim_end() == FRONT_MATTER_DELIMITER => break true,
+                Some (l) => {
+                    end += l.len();
+                    line += 1;
+                },
+                None => break false,
+            }
+        };
+
+        if !closed {
+            let location = BlogErrorLocation {
+                line: 1,
+                column: 1,
+                length: FRONT_MATTER_DELIMITER.len(),
+            };
+
+            return result.err(BlogError::ParseError {
+                message: "unclosed front matter".to_string(),
+                filename: filename.to_owned(),
+                excerpt: location.excerpt(source),
+                hint: Some (format!("close the front matter with a line containing only `{}`", FRONT_MATTER_DELIMITER)),
+                location,
+            });
+        }
+
+        // Parse the TOML into a front matter structure
+        let toml = source.get(start..end).unwrap_or_default();
+        let front_matter = match toml::from_str(toml) {
+            Ok (f) => f,
+            Err (e) => {
+                // Find the location of the error in the source file
+                let location = e.span()
+                    .map(|s| BlogErrorLocation::from_offsets(source, start + s.start, start + s.end))
+                    .unwrap_or_default();
+
+                return result.err(BlogError::ParseError {
+                    message: format!("invalid front matter: {}", e.message()),
+                    filename: filename.to_owned(),
+                    excerpt: location.excerpt(source),
+                    hint: None,
+                    location,
+                });
+            },
+        };
+
+        // The rest of the source begins after the closing delimiter
+        let body = source.get(end..)
+            .and_then(|b| b.split_once('\n'))
+            .map(|(_, b)| b)
+            .unwrap_or_default();
+
+        result.ok((front_matter, body, line + 1))
+    }
+}
+
+/// Deserialize a date, written either as a string or as a TOML date.
+fn date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    match toml::Value::deserialize(deserializer)? {
+        toml::Value::String (s) => Ok (Some (s)),
+        toml::Value::Datetime (d) => Ok (Some (d.to_string())),
+        _ => Err (D::Error::custom("expected a date")),
+    }
+}
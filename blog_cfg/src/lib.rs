@@ -7,9 +7,18 @@
 #![deny(missing_docs)]
 
 mod config;
+mod frontmatter;
 
 pub use config::{
     Config,
+    ControlArgs,
+    ControlConfig,
+    MathConfig,
+    MathRender,
+    NoteStyle,
+    Numbering,
     SiteConfig,
     SiteStyle,
-};
\ No newline at end of file
+};
+
+pub use frontmatter::FrontMatter;
\ No newline at end of file
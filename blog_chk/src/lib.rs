@@ -6,7 +6,16 @@
 // Enforce all documentation.
 #![deny(missing_docs)]
 
-use std::path::Path;
+use std::{
+    collections::HashSet,
+    mem,
+    path::Path,
+};
+
+use blog_cfg::{
+    Config,
+    FrontMatter,
+};
 
 use blog_err::{
     BlogError,
@@ -16,76 +25,545 @@ use blog_err::{
 
 use blog_prs::{
     Expression,
+    Node,
     ParseError,
 };
 
+use blog_tkn::{
+    Span,
+    Token,
+    TokenClass,
+    Tokenizer,
+};
+
 /// Validate a list of expressions.
 /// 
 /// # Parameters
-/// - `expressions` (`Vec<Expression>`): a reference to the list
-///     of expressions to validate
-/// - `filename` (`&str`): the filename of the file being checked
+/// - `nodes` (`&[Node]`): a reference to the list of expressions
+///   to validate
+/// - `filename` (`&Path`): the path of the file being checked
+/// - `source` (`&str`): the source code of the file being checked
+/// - `config` (`&Config`): a reference to the configuration
+///   information
 /// 
 /// # Returns
 /// A `BlogResult<()>` indicating if parsing was successful.
 ///
 /// **Note**: if unrecoverable errors were found in parsing, then
 /// this function exits.
-pub fn validate(expressions: &[Expression], filename: &Path) -> BlogResult<()> {
+pub fn validate(nodes: &[Node], filename: &Path, source: &str, config: &Config) -> BlogResult<()> {
     // Construct user-friendly output
     let mut result = BlogResult::default();
 
     // Iterate over all expressions
-    for (i, expression) in expressions.iter().enumerate() {
-        // If this expression is an error, construct an error message
-        // 
-        // All errors must occur at the top level, so we don't need to
-        // recurse through nested expressions
-        if let Expression::Error (p) = expression {
-            // Check individual expressions
-            result = result.err(construct_error(p, filename, i, expressions));
+    for node in nodes {
+        // Find any errors in this expression
+        let mut errors = Vec::new();
+        collect_errors(&node.expression, &mut errors);
+
+        // Construct an error message for each error
+        for (p, span) in errors {
+            result = result.err(construct_error(p, filename, source, span));
         }
     }
 
+    // Make sure that raw HTML is allowed, if it is used, including
+    //  inside of the body of a block
+    if config.site.raw_html == Some (false) {
+        for node in nodes {
+            walk_page(&node.expression, &mut |expr| if let Expression::RawHtml (_) = expr {
+                result = mem::take(&mut result).err(construct_error(&ParseError::RawHtmlDisabled, filename, source, node.span));
+            });
+        }
+    }
+
+    // Make sure that footnotes are used consistently
+    for (p, span) in check_footnotes(nodes) {
+        result = result.err(construct_error(&p, filename, source, span));
+    }
+
+    // Make sure that math blocks and equations are balanced
+    for (p, span) in check_blocks(nodes).into_iter().chain(check_equations(filename, source)) {
+        result = result.err(construct_error(&p, filename, source, span));
+    }
+
+    result
+}
+
+/// Check the cross-references and reference-style links in a list of
+/// expressions, once they have been resolved.
+/// 
+/// Unlike `validate`, this checks the contents of included files as
+/// well, as references are resolved for the page as a whole.
+/// Errors inside of an included file are reported at the `include`
+/// control sequence.
+/// 
+/// # Parameters
+/// - `nodes` (`&[Node]`): a reference to the list of expressions
+///   to check
+/// - `filename` (`&Path`): the path of the file being checked
+/// - `source` (`&str`): the source code of the file being checked
+/// 
+/// # Returns
+/// A `BlogResult<()>` indicating if every reference was resolved.
+pub fn check_references(nodes: &[Node], filename: &Path, source: &str) -> BlogResult<()> {
+    let mut result = BlogResult::default();
+
+    // Walk the page once, so that errors are reported in source order
+    let mut labels = HashSet::new();
+    let mut duplicates = HashSet::new();
+    let mut undefined = HashSet::new();
+    let mut links = HashSet::new();
+    for node in nodes {
+        walk_included(&node.expression, false, &mut |expr, included| {
+            use Expression::*;
+            let (error, span) = match expr {
+                // Report each duplicate label once
+                MathBlock { label: Some (l), .. } if !labels.insert(l.to_owned()) && duplicates.insert(l.to_owned()) => {
+                    (ParseError::DuplicateLabel (l.to_owned()), node.span)
+                },
+
+                // Report the first reference to each unknown label
+                Ref { label, href, span, .. } if href.is_empty() && undefined.insert(label.to_owned()) => {
+                    (ParseError::UndefinedLabel (label.to_owned()), *span)
+                },
+
+                // Report the first link to each undefined link reference
+                LinkRef { label, href, span, .. } if href.is_empty() && links.insert(label.to_lowercase()) => {
+                    (ParseError::UndefinedLink (label.to_owned()), *span)
+                },
+
+                _ => return,
+            };
+
+            // Locations inside of an included file refer to that file
+            let span = if included {
+                node.span
+            } else {
+                span
+            };
+
+            result = mem::take(&mut result).err(construct_error(&error, filename, source, span));
+        });
+    }
+
     result
 }
 
+/// Check that every footnote is defined exactly once.
+/// 
+/// # Parameters
+/// - `nodes` (`&[Node]`): the list of expressions to check
+/// 
+/// # Returns
+/// A `Vec<(ParseError, Span)>` containing any errors and the
+/// locations of the expressions in which they occur.
+fn check_footnotes(nodes: &[Node]) -> Vec<(ParseError, Span)> {
+    let mut errors = Vec::new();
+
+    // Find all definitions, reporting any duplicates
+    let mut defined = HashSet::new();
+    for node in nodes {
+        walk_page(&node.expression, &mut |expr| if let Expression::Footnote { label, span, .. } = expr {
+            if !defined.insert(label.to_owned()) {
+                errors.push((ParseError::DuplicateFootnote (label.to_owned()), *span));
+            }
+        });
+    }
+
+    // Report the first reference to each undefined footnote
+    let mut reported = HashSet::new();
+    for node in nodes {
+        walk_page(&node.expression, &mut |expr| if let Expression::FootnoteRef { label, span, .. } = expr {
+            if !defined.contains(label) && reported.insert(label.to_owned()) {
+                errors.push((ParseError::UndefinedFootnote (label.to_owned()), *span));
+            }
+        });
+    }
+
+    errors
+}
+
+/// Check that every `::endmath` closes a math block and that every
+/// `::qed` is inside of a proof.
+/// 
+/// # Parameters
+/// - `nodes` (`&[Node]`): the list of expressions to check
+/// 
+/// # Returns
+/// A `Vec<(ParseError, Span)>` containing any errors and their
+/// locations.
+fn check_blocks(nodes: &[Node]) -> Vec<(ParseError, Span)> {
+    let mut errors = Vec::new();
+
+    for node in nodes {
+        check_block(&node.expression, false, &mut errors);
+    }
+
+    errors
+}
+
+/// Check the math blocks in an expression.
+/// 
+/// # Parameters
+/// - `expression` (`&Expression`): the expression to check
+/// - `proof` (`bool`): whether the expression is inside of a proof
+/// - `errors` (`&mut Vec<(ParseError, Span)>`): the list of errors
+///   and their locations
+/// 
+/// # Returns
+/// None.
+fn check_block(expression: &Expression, proof: bool, errors: &mut Vec<(ParseError, Span)>) {
+    use Expression::*;
+    let proof = match expression {
+        // Blocks consume the `::endmath` that closes them, so any
+        //  other `::endmath` has no block to close
+        EndMath (span) => {
+            errors.push((ParseError::UnopenedBlock ("endmath".to_string()), *span));
+            return;
+        },
+        Qed (span) => {
+            if !proof {
+                errors.push((ParseError::QedOutsideProof, *span));
+            }
+            return;
+        },
+
+        // Included files are checked on their own
+        Include { .. } => return,
+
+        MathBlock { blocktype, .. } => blocktype.to_lowercase().starts_with("proof"),
+        _ => proof,
+    };
+
+    for child in expression.children() {
+        check_block(child, proof, errors);
+    }
+}
+
+/// Check that every equation delimiter is matched.
+/// 
+/// Equations are found by tokenizing the source again, since the
+/// delimiters are kept as plain text by the parser.  Until an equation
+/// is closed, the tokenizer does not recognize emphasis, so an unclosed
+/// delimiter changes the meaning of the rest of the file.
+/// 
+/// # Parameters
+/// - `filename` (`&Path`): the path of the file being checked
+/// - `source` (`&str`): the source code of the file being checked
+/// 
+/// # Returns
+/// A `Vec<(ParseError, Span)>` containing any errors and their
+/// locations.
+fn check_equations(filename: &Path, source: &str) -> Vec<(ParseError, Span)> {
+    let mut errors = Vec::new();
+
+    // Errors in the front matter are reported elsewhere
+    let (_, body, line) = if let BlogResult::Ok (s) = FrontMatter::split(source, filename) {
+        s
+    } else {
+        return errors;
+    };
+
+    // The delimiter of the equation currently open
+    let mut open: Option<(String, Span)> = None;
+
+    let mut tokenizer = Tokenizer::from_line(body.to_owned(), line);
+    while let Some (t) = tokenizer.next() {
+        if t.class != TokenClass::Paragraph {
+            continue;
+        }
+
+        for (d, span) in delimiters(&t) {
+            match d.as_str() {
+                "\\[" | "\\(" => if let Some ((o, s)) = open.replace((d, span)) {
+                    errors.push((ParseError::UnclosedEquation (o), s));
+                },
+                "\\]" | "\\)" => match open.take() {
+                    Some ((o, _)) if matches!((o.as_str(), d.as_str()), ("\\[", "\\]") | ("\\(", "\\)")) => (),
+                    Some ((o, _)) => errors.push((ParseError::MismatchedEquation {
+                        open: o,
+                        close: d,
+                    }, span)),
+                    None => errors.push((ParseError::UnopenedEquation (d), span)),
+                },
+                _ => (),
+            }
+        }
+    }
+
+    if let Some ((o, s)) = open {
+        errors.push((ParseError::UnclosedEquation (o), s));
+    }
+
+    errors
+}
+
+/// Find the equation delimiters in a text token.
+/// 
+/// Delimiters are usually tokens of their own, but are kept inside of
+/// the text of square brackets, such as the arguments of a control
+/// sequence.  Escaped brackets and parentheses have already lost their
+/// backslashes.
+/// 
+/// # Parameters
+/// - `token` (`&Token`): the text token
+/// 
+/// # Returns
+/// A `Vec<(String, Span)>` containing each delimiter and its location.
+fn delimiters(token: &Token) -> Vec<(String, Span)> {
+    let mut output = Vec::new();
+
+    let mut line = token.span.line;
+    let mut column = token.span.column;
+
+    let mut chars = token.value.chars();
+    while let Some (c) = chars.next() {
+        match c {
+            '\\' => {
+                let next = chars.next();
+
+                if let Some (n @ ('[' | ']' | '(' | ')')) = next {
+                    output.push((format!("\\{}", n), Span {
+                        line,
+                        column,
+                        length: 2,
+                    }));
+                }
+
+                // Skip the character after the backslash, such as in `\\`
+                match next {
+                    Some ('\n') => {
+                        line += 1;
+                        column = 1;
+                    },
+                    Some (_) => column += 2,
+                    None => column += 1,
+                }
+            },
+            '\n' => {
+                line += 1;
+                column = 1;
+            },
+            _ => column += 1,
+        }
+    }
+
+    output
+}
+
+/// Collect all parse errors in an expression.
+/// 
+/// # Parameters
+/// - `expression` (`&Expression`): the expression to search
+/// - `errors` (`&mut Vec<(&ParseError, Span)>`): the list of errors
+///   and their locations
+/// 
+/// # Returns
+/// None.
+fn collect_errors<'a>(expression: &'a Expression, errors: &mut Vec<(&'a ParseError, Span)>) {
+    if let Expression::Error (p, span) = expression {
+        errors.push((p, *span));
+    }
+
+    // Included files are checked on their own
+    if let Expression::Include { .. } = expression {
+        return;
+    }
+
+    // Errors in nested expressions are kept inside of their parent
+    for child in expression.children() {
+        collect_errors(child, errors);
+    }
+}
+
+/// Apply a function to an expression and to every expression nested
+/// inside of it, except for the contents of included files.
+/// 
+/// # Parameters
+/// - `expression` (`&Expression`): the expression
+/// - `f` (`&mut impl FnMut(&Expression)`): the function
+/// 
+/// # Returns
+/// None.
+fn walk_page(expression: &Expression, f: &mut impl FnMut(&Expression)) {
+    f(expression);
+
+    // Included files are checked on their own
+    if let Expression::Include { .. } = expression {
+        return;
+    }
+
+    for child in expression.children() {
+        walk_page(child, f);
+    }
+}
+
+/// Apply a function to an expression and to every expression nested
+/// inside of it, including the contents of included files.
+/// 
+/// # Parameters
+/// - `expression` (`&Expression`): the expression
+/// - `included` (`bool`): whether the expression is inside of an
+///   included file
+/// - `f` (`&mut impl FnMut(&Expression, bool)`): the function, which
+///   is also told whether each expression is inside of an included file
+/// 
+/// # Returns
+/// None.
+fn walk_included(expression: &Expression, included: bool, f: &mut impl FnMut(&Expression, bool)) {
+    f(expression, included);
+
+    let included = included || matches!(expression, Expression::Include { .. });
+
+    for child in expression.children() {
+        walk_included(child, included, f);
+    }
+}
+
 /// Construct an error message.
 /// 
 /// # Parameters
 /// - `p` (`&ParseError`): the parse error
 /// - `filename` (`&Path`): the file path of the error
-/// - `i` (`usize`): the index of the erroneous expression in the list of expressions
-/// - `expressions` (`&Vec<Expression>`): a reference to the list of expressions
+/// - `source` (`&str`): the source code of the file
+/// - `span` (`Span`): the location of the offending token
 /// 
 /// # Returns
 /// A `BlogError` representing the error message.
-fn construct_error(p: &ParseError, filename: &Path, i: usize, expressions: &[Expression]) -> BlogError {
+fn construct_error(p: &ParseError, filename: &Path, source: &str, span: Span) -> BlogError {
     // Find location of error
-    let mut location = BlogErrorLocation::Beginning;
-
-    // Iterate backwards to find nearest valid expression
-    let mut j = i;
-    while j > 0 {
-        let prev = &expressions[j - 1];
-        if let Expression::Error (_) = prev {
-            // Skip, not a valid expression
-        } else if let Expression::Newline = prev {
-            // Skip, not useful
-        } else {
-            // Found the nearest valid expression!
-            location = BlogErrorLocation::After (prev.to_string());
-            break;
-        }
-
-        // Go back one more expresion
-        j -= 1;
-    }
+    let location = BlogErrorLocation {
+        line: span.line,
+        column: span.column,
+        length: span.length,
+    };
 
     // Construct error message
     BlogError::ParseError {
         filename: filename.to_owned(),
         message: p.to_string(),
+        excerpt: location.excerpt(source),
+        hint: p.hint(),
         location,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use blog_cfg::Config;
+
+    use blog_err::{
+        BlogError,
+        BlogErrorLocation,
+        BlogResult,
+    };
+
+    use blog_prs::Parser;
+
+    use blog_tkn::Tokenizer;
+
+    use super::{
+        check_references,
+        validate,
+    };
+
+    /// Validate a source string with raw HTML disabled.
+    fn validate_locked(source: &str) -> BlogResult<()> {
+        let nodes = Parser::new().parse(&mut Tokenizer::from(source.to_owned()));
+
+        let mut config = Config::default();
+        config.site.raw_html = Some (false);
+
+        validate(&nodes, Path::new("source/test.md"), source, &config)
+    }
+
+    /// Get the location of each error in a result.
+    fn locations(result: BlogResult<()>) -> Vec<BlogErrorLocation> {
+        let errors = match result {
+            BlogResult::Err (e) => e,
+            BlogResult::Ok (_) => Vec::new(),
+        };
+
+        errors.iter()
+            .filter_map(|e| match e.downcast_ref::<BlogError>() {
+                Some (BlogError::ParseError { location, .. }) => Some (*location),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_raw_html_is_rejected() {
+        let sources = [
+            "::notice\n::html\n<script>alert(1)</script>\n::end\n::end\n",
+            "::mathblock[Theorem][Title]\n::html\n<script>alert(1)</script>\n::end\n::end\n",
+        ];
+
+        for source in sources {
+            assert!(matches!(validate_locked(source), BlogResult::Err (e) if e.len() == 1), "{}", source);
+        }
+    }
+
+    #[test]
+    fn footnote_errors_are_reported_at_the_label() {
+        let locations = locations(validate_locked("More text with a note[^missing]\n\n[^a]: One\n[^a]: Two\n"));
+
+        assert_eq!(locations, [
+            BlogErrorLocation { line: 4, column: 1, length: 4 },
+            BlogErrorLocation { line: 1, column: 22, length: 10 },
+        ]);
+    }
+
+    #[test]
+    fn undefined_label_is_reported_at_the_label() {
+        let source = "Some text, as in ::ref[nowhere] here.\n";
+        let nodes = Parser::new().parse(&mut Tokenizer::from(source.to_owned()));
+
+        assert_eq!(locations(check_references(&nodes, Path::new("source/test.md"), source)), [
+            BlogErrorLocation { line: 1, column: 23, length: 9 },
+        ]);
+    }
+
+    #[test]
+    fn unbalanced_equations_are_reported() {
+        let sources = [
+            "Before \\[ x^2 + 1\n",
+            "Before \\( x^2 + 1\n",
+            "Before \\( x^2 \\] after\n",
+            "::notice[See \\( x \\] here]\n",
+        ];
+
+        for source in sources {
+            assert!(matches!(validate_locked(source), BlogResult::Err (e) if e.len() == 1), "{}", source);
+        }
+    }
+
+    #[test]
+    fn escaped_brackets_are_not_equations() {
+        let source = "See \\[1] and \\(sic) here.\n";
+
+        assert!(matches!(validate_locked(source), BlogResult::Ok (_)));
+    }
+
+    #[test]
+    fn reference_errors_are_in_source_order() {
+        let source = "[a link][missing]\n\nSee ::ref[nowhere].\n";
+        let nodes = Parser::new().parse(&mut Tokenizer::from(source.to_owned()));
+
+        let errors = match check_references(&nodes, Path::new("source/test.md"), source) {
+            BlogResult::Err (e) => e,
+            _ => panic!("expected errors"),
+        };
+
+        let lines: Vec<String> = errors.iter().map(|e| format!("{:?}", e)).collect();
+        assert_eq!(locations(BlogResult::Err (errors)), [
+            BlogErrorLocation { line: 1, column: 1, length: 17 },
+            BlogErrorLocation { line: 3, column: 10, length: 9 },
+        ]);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("missing") && lines[1].contains("nowhere"), "{:?}", lines);
+    }
+}
@@ -74,7 +74,7 @@ impl Subcommand {
     /// 
     /// # Parameters
     /// - `string` (`Option<String>`): the provided subcommand,
-    ///     if one was provided
+    ///   if one was provided
     /// 
     /// # Returns
     /// A `Subcommand` containing the subcommand.
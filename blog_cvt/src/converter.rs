@@ -0,0 +1,278 @@
+//! Configurable source-to-output converter.
+
+use std::{
+    fs,
+    path::Path,
+    sync::OnceLock,
+};
+
+use colored::*;
+
+use blog_cfg::{
+    Config,
+    ControlArgs,
+    FrontMatter,
+    MathRender,
+};
+
+use blog_chk::{
+    check_references,
+    validate,
+};
+
+use blog_emt::Emitter;
+
+use blog_err::{
+    BlogError,
+    BlogResult,
+    unwrap_or_return,
+};
+
+use blog_env::{
+    SOURCE_DIR_NAME,
+    SOURCE_FILE_EXT,
+};
+
+use blog_mth::render;
+
+use blog_prs::{
+    CustomControl,
+    Node,
+    Parser,
+    Expression,
+    assign_anchors,
+    number_footnotes,
+    render_equations,
+    resolve_links,
+    resolve_refs,
+};
+
+use blog_tkn::Tokenizer;
+
+use crate::{
+    include::{
+        flatten,
+        resolve,
+    },
+    labels::{
+        Targets,
+        index,
+        number,
+    },
+};
+
+/// A function that extends a parser.
+pub type Extension = Box<dyn Fn(&mut Parser)>;
+
+#[derive(Default)]
+/// A converter from source files to output files, which may be
+/// extended with new syntax.
+/// 
+/// Extensions are applied to the parser for each source file,
+/// where they may register parselets (see `Parser::register`) or
+/// define control sequences (see `Parser::define`).
+pub struct Converter {
+    /// Extensions to the parser.
+    extensions: Vec<Extension>,
+
+    /// Labelled math blocks on every page of the site, found the first
+    /// time that a page refers to another page.
+    labels: OnceLock<Targets>,
+}
+
+impl Converter {
+    /// Construct a new converter with no extensions.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A new `Converter`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an extension to this converter.
+    /// 
+    /// # Parameters
+    /// - `extension` (`impl Fn(&mut Parser)`): a function that extends
+    ///   the parser
+    /// 
+    /// # Returns
+    /// This `Converter`, with the extension added.
+    pub fn extend(mut self, extension: impl Fn(&mut Parser) + 'static) -> Self {
+        self.extensions.push(Box::new(extension));
+        self
+    }
+
+    /// Convert a source file into an output file.
+    /// 
+    /// # Parameters
+    /// - `source` (`String`): the source code
+    /// - `root` (`&Path`): the root directory of the site
+    /// - `filename` (`&Path`): the filename
+    /// - `config` (`&Config`): a reference to the configuration
+    ///   information
+    /// - `verbosity` (`usize`): the verbosity level
+    ///
+    /// # Returns
+    /// A `BlogResult<Option<String>>` containing the HTML output code (or
+    /// `None` if the page is a draft) or any errors.
+    pub fn convert(&self, source: String, root: &Path, filename: &Path, config: &Config, verbosity: usize) -> BlogResult<Option<String>> {
+        // Construct the path of the source file, relative to the site root
+        let source_file = Path::new(SOURCE_DIR_NAME).join(filename).with_extension(SOURCE_FILE_EXT);
+
+        // Split off the front matter
+        let (front_matter, body, line) = unwrap_or_return!(FrontMatter::split(&source, &source_file));
+
+        // Drafts are not built
+        if front_matter.draft {
+            return BlogResult::Ok (None);
+        }
+
+        // Construct a new tokenizer
+        let mut tokenizer = Tokenizer::from_line(body.to_owned(), line);
+
+        // Construct a new parser
+        let parser = self.parser(config);
+
+        // Parse tokens
+        let mut nodes = parser.parse(&mut tokenizer);
+
+        // Print each expression, if very verbose
+        if verbosity > 2 {
+            for node in &nodes {
+                // Errors inside of paragraphs are displayed with
+                //  their paragraph
+                match &node.expression {
+                    Expression::Error (e, span) => println!("{:>12} {} ({})", "Error".bright_red(), e, span),
+                    expr => println!("{:>12} '{}' ({})", "Parsed".bright_yellow(), expr, node.span),
+                }
+            }
+        }
+
+        // Fill in included files, starting from this one
+        let mut stack = vec![fs::canonicalize(root.join(&source_file)).unwrap_or(source_file.clone())];
+        let mut result = resolve(&mut nodes, &parser, root, config, &mut stack);
+
+        // Number footnotes again, now that included files are in place
+        number_footnotes(&mut nodes);
+
+        // Links may be defined in included files
+        resolve_links(&mut nodes);
+
+        // Headers in included files need anchors too
+        assign_anchors(&mut nodes);
+
+        // Number math blocks and resolve cross-references to them
+        let targets = self.targets(&mut nodes, &parser, root, config);
+        resolve_refs(&mut nodes, &targets);
+
+        // Validate parser output or return errors
+        let mut errors = Vec::new();
+        if let BlogResult::Err (e) = validate(&nodes, &source_file, &source, config) {
+            errors.extend(e);
+        }
+        if let BlogResult::Err (e) = check_references(&nodes, &source_file, &source) {
+            errors.extend(e);
+        }
+
+        // Report errors in source order, whichever check found them
+        errors.sort_by_key(|e| match e.downcast_ref::<BlogError>() {
+            Some (BlogError::ParseError { location, .. }) => (location.line, location.column),
+            _ => (0, 0),
+        });
+        if !errors.is_empty() {
+            result = result.errs(errors);
+        }
+        unwrap_or_return!(result);
+
+        // Render equations to MathML, if requested
+        if config.math.as_ref().and_then(|m| m.render) == Some (MathRender::Mathml) {
+            render_equations(&mut nodes, &render);
+        }
+
+        // Discard locations, as they are no longer needed
+        let expressions = flatten(nodes);
+
+        // Construct a new emitter
+        let emitter = Emitter::new(config);
+
+        // Emit HTML or return errors
+        let output = unwrap_or_return!(emitter.emit(expressions, root, filename, &front_matter));
+
+        BlogResult::Ok (Some (output))
+    }
+
+    /// Number the math blocks in a list of nodes, and find the block
+    /// that each cross-reference refers to.
+    /// 
+    /// Labels on the page itself take precedence over labels on other
+    /// pages of the site.
+    /// 
+    /// # Parameters
+    /// - `nodes` (`&mut [Node]`): the list of nodes
+    /// - `parser` (`&Parser`): the parser for other pages
+    /// - `root` (`&Path`): the root directory of the site
+    /// - `config` (`&Config`): a reference to the configuration
+    ///   information
+    /// 
+    /// # Returns
+    /// A `Targets` map containing the display text and URI of each label.
+    fn targets(&self, nodes: &mut [Node], parser: &Parser, root: &Path, config: &Config) -> Targets {
+        let mut targets: Targets = number(nodes, config).into_iter()
+            .map(|(label, text)| (label.to_owned(), (text, format!("#{}", label))))
+            .collect();
+
+        // Only look at other pages if this page needs them
+        let mut external = false;
+        for node in nodes.iter() {
+            node.expression.walk(&mut |expr| if let Expression::Ref { label, .. } = expr {
+                external |= !targets.contains_key(label);
+            });
+        }
+
+        if external {
+            for (label, target) in self.labels.get_or_init(|| index(parser, root, config)) {
+                targets.entry(label.to_owned()).or_insert_with(|| target.to_owned());
+            }
+        }
+
+        targets
+    }
+
+    /// Construct a parser that recognizes the control sequences defined
+    /// by the site and by each extension.
+    /// 
+    /// # Parameters
+    /// - `config` (`&Config`): a reference to the configuration
+    ///   information
+    /// 
+    /// # Returns
+    /// A new `Parser`.
+    pub(crate) fn parser(&self, config: &Config) -> Parser {
+        let mut parser = Parser::new();
+
+        for (name, c) in config.controls.iter().flatten() {
+            use ControlArgs::*;
+            let control = match &c.args {
+                Count (n) => CustomControl::new(*n, &c.html),
+                Names (n) => CustomControl::named(n, &c.html),
+            };
+
+            let control = if c.block == Some (true) {
+                control.with_body()
+            } else {
+                control
+            };
+
+            parser.define(name, control);
+        }
+
+        for extend in &self.extensions {
+            extend(&mut parser);
+        }
+
+        parser
+    }
+}
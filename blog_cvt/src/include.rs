@@ -0,0 +1,241 @@
+//! Included source files for the Blog Builder.
+
+use std::{
+    fs,
+    mem,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use blog_cfg::{
+    Config,
+    FrontMatter,
+};
+
+use blog_chk::validate;
+
+use blog_err::{
+    BlogResult,
+    unwrap_or_return,
+};
+
+use blog_env::{
+    MAX_INCLUDE_DEPTH,
+    SOURCE_DIR_NAME,
+    SOURCE_FILE_EXT,
+};
+
+use blog_prs::{
+    Expression,
+    Node,
+    ParseError,
+    Parser,
+};
+
+use blog_tkn::{
+    Span,
+    Tokenizer,
+};
+
+
+/// Fill in the contents of every included file in a list of nodes.
+/// 
+/// Included files that cannot be read, that include themselves, or
+/// that are nested too deeply are replaced by parse errors, which are
+/// reported by the checker.  Errors inside of an included file are
+/// reported against that file.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// - `parser` (`&Parser`): the parser for included files
+/// - `root` (`&Path`): the root directory of the site
+/// - `config` (`&Config`): a reference to the configuration
+///   information
+/// - `stack` (`&mut Vec<PathBuf>`): the files currently being included,
+///   starting with the page itself
+/// 
+/// # Returns
+/// A `BlogResult<()>` containing any errors in included files.
+pub fn resolve(nodes: &mut [Node], parser: &Parser, root: &Path, config: &Config, stack: &mut Vec<PathBuf>) -> BlogResult<()> {
+    let mut result = BlogResult::default();
+
+    for node in nodes.iter_mut() {
+        resolve_expr(&mut node.expression, node.span, parser, root, config, stack, &mut result);
+    }
+
+    result
+}
+
+/// Discard the locations of a list of nodes, replacing each included
+/// file with its expressions.
+/// 
+/// # Parameters
+/// - `nodes` (`Vec<Node>`): the list of nodes
+/// 
+/// # Returns
+/// A `Vec<Expression>` containing the expressions.
+pub fn flatten(nodes: Vec<Node>) -> Vec<Expression> {
+    nodes.into_iter()
+        .flat_map(|n| match n.expression {
+            Expression::Include { expressions, .. } => expressions,
+            expression => vec![expression],
+        })
+        .collect()
+}
+
+/// Fill in the contents of every included file in an expression.
+/// 
+/// # Parameters
+/// - `expression` (`&mut Expression`): the expression
+/// - `span` (`Span`): the location of the expression, for errors
+/// - `parser` (`&Parser`): the parser for included files
+/// - `root` (`&Path`): the root directory of the site
+/// - `config` (`&Config`): a reference to the configuration
+///   information
+/// - `stack` (`&mut Vec<PathBuf>`): the files currently being included
+/// - `result` (`&mut BlogResult<()>`): any errors in included files
+/// 
+/// # Returns
+/// None.
+fn resolve_expr(expression: &mut Expression, span: Span, parser: &Parser, root: &Path, config: &Config, stack: &mut Vec<PathBuf>, result: &mut BlogResult<()>) {
+    if let Expression::Include { path, expressions } = expression {
+        match include(path, parser, root, config, stack) {
+            Ok (BlogResult::Ok (e)) => *expressions = e,
+            Ok (BlogResult::Err (e)) => *result = mem::take(result).errs(e),
+            Err (p) => *expression = Expression::Error (p, span),
+        }
+
+        return;
+    }
+
+    for child in expression.children_mut() {
+        resolve_expr(child, span, parser, root, config, stack, result);
+    }
+}
+
+/// Read, parse, and check an included file.
+/// 
+/// # Parameters
+/// - `path` (`&str`): the path of the file, relative to the source
+///   directory
+/// - `parser` (`&Parser`): the parser for included files
+/// - `root` (`&Path`): the root directory of the site
+/// - `config` (`&Config`): a reference to the configuration
+///   information
+/// - `stack` (`&mut Vec<PathBuf>`): the files currently being included
+/// 
+/// # Returns
+/// A `Result` containing the expressions of the file (or any errors
+/// inside of it), or a `ParseError` if the file cannot be included.
+fn include(path: &str, parser: &Parser, root: &Path, config: &Config, stack: &mut Vec<PathBuf>) -> Result<BlogResult<Vec<Expression>>, ParseError> {
+    // Includes may only be nested so deep
+    if stack.len() > MAX_INCLUDE_DEPTH {
+        return Err (ParseError::IncludeTooDeep (MAX_INCLUDE_DEPTH));
+    }
+
+    // Construct the path of the included file, relative to the site root
+    let mut source_file = Path::new(SOURCE_DIR_NAME).join(path.trim_start_matches('/'));
+    if source_file.extension().is_none() {
+        source_file.set_extension(SOURCE_FILE_EXT);
+    }
+
+    // Compare canonical paths, so that different spellings
+    //  of the same path are recognized
+    let canonical = fs::canonicalize(root.join(&source_file))
+        .map_err(|_| ParseError::IncludeNotFound (path.to_owned()))?;
+
+    // Never publish files from outside of the source directory
+    let source_dir = fs::canonicalize(root.join(SOURCE_DIR_NAME))
+        .map_err(|_| ParseError::IncludeNotFound (path.to_owned()))?;
+    if !canonical.starts_with(&source_dir) {
+        return Err (ParseError::IncludeOutsideSource (path.to_owned()));
+    }
+
+    // Read the source
+    let source = fs::read_to_string(&canonical)
+        .map_err(|_| ParseError::IncludeNotFound (path.to_owned()))?;
+
+    if stack.contains(&canonical) {
+        return Err (ParseError::CircularInclude (path.to_owned()));
+    }
+
+    stack.push(canonical);
+    let nodes = parse(&source, &source_file, parser, root, config, stack);
+    stack.pop();
+
+    Ok (match nodes {
+        BlogResult::Ok (n) => BlogResult::Ok (flatten(n)),
+        BlogResult::Err (e) => BlogResult::Err (e),
+    })
+}
+
+/// Parse and check an included file.
+/// 
+/// The front matter of an included file is ignored.
+/// 
+/// # Parameters
+/// - `source` (`&str`): the source code
+/// - `source_file` (`&Path`): the path of the file, relative to the
+///   site root
+/// - `parser` (`&Parser`): the parser for included files
+/// - `root` (`&Path`): the root directory of the site
+/// - `config` (`&Config`): a reference to the configuration
+///   information
+/// - `stack` (`&mut Vec<PathBuf>`): the files currently being included
+/// 
+/// # Returns
+/// A `BlogResult<Vec<Node>>` containing the nodes or any errors.
+fn parse(source: &str, source_file: &Path, parser: &Parser, root: &Path, config: &Config, stack: &mut Vec<PathBuf>) -> BlogResult<Vec<Node>> {
+    // Split off the front matter
+    let (_, body, line) = unwrap_or_return!(FrontMatter::split(source, source_file));
+
+    // Parse tokens
+    let mut tokenizer = Tokenizer::from_line(body.to_owned(), line);
+    let mut nodes = parser.parse(&mut tokenizer);
+
+    // Fill in any nested includes
+    let mut result = resolve(&mut nodes, parser, root, config, stack);
+
+    // Check the file on its own
+    if let BlogResult::Err (e) = validate(&nodes, source_file, source, config) {
+        result = result.errs(e);
+    }
+
+    match result {
+        BlogResult::Ok (_) => BlogResult::Ok (nodes),
+        BlogResult::Err (e) => BlogResult::Err (e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs,
+    };
+
+    use blog_cfg::Config;
+
+    use blog_env::SOURCE_DIR_NAME;
+
+    use blog_prs::{
+        ParseError,
+        Parser,
+    };
+
+    use super::include;
+
+    #[test]
+    fn include_outside_source_is_rejected() {
+        let root = env::temp_dir().join(format!("blog-include-{}", std::process::id()));
+        fs::create_dir_all(root.join(SOURCE_DIR_NAME)).unwrap();
+        fs::write(root.join("secret.md"), "Secret").unwrap();
+
+        let result = include("../secret", &Parser::new(), &root, &Config::default(), &mut Vec::new());
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(matches!(result, Err (ParseError::IncludeOutsideSource (p)) if p == "../secret"));
+    }
+}
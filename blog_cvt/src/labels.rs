@@ -0,0 +1,125 @@
+//! Labelled math blocks across the pages of a site.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+};
+
+use blog_cfg::{
+    Config,
+    FrontMatter,
+    Numbering,
+};
+
+use blog_env::{
+    OUTPUT_FILE_EXT,
+    SOURCE_DIR_NAME,
+    SOURCE_FILE_EXT,
+};
+
+use blog_err::BlogResult;
+
+use blog_prs::{
+    Node,
+    Parser,
+    number_blocks,
+};
+
+use blog_str::pages;
+
+use blog_tkn::Tokenizer;
+
+use crate::include::resolve;
+
+/// The display text and URI of each label.
+pub type Targets = HashMap<String, (String, String)>;
+
+/// Number the math blocks in a list of nodes, as configured by the site.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// - `config` (`&Config`): a reference to the configuration
+///   information
+/// 
+/// # Returns
+/// A `Vec<(String, String)>` containing the label and the display
+/// text of each labelled block.
+pub fn number(nodes: &mut [Node], config: &Config) -> Vec<(String, String)> {
+    let math = config.math.to_owned().unwrap_or_default();
+    let sections = math.numbering == Some (Numbering::Section);
+
+    number_blocks(nodes, sections, |t| math.numbered(t))
+}
+
+/// Find the labelled math blocks on every page of a site.
+/// 
+/// Pages that cannot be read or parsed are skipped, as their errors
+/// are reported when they are built.  If two pages use the same label,
+/// the first page in alphabetical order is used.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the parser for each page
+/// - `root` (`&Path`): the root directory of the site
+/// - `config` (`&Config`): a reference to the configuration
+///   information
+/// 
+/// # Returns
+/// A `Targets` map containing the display text and URI of each label.
+pub fn index(parser: &Parser, root: &Path, config: &Config) -> Targets {
+    let mut targets = Targets::new();
+
+    let mut files = pages(&root.join(SOURCE_DIR_NAME));
+    files.sort();
+
+    for file in files {
+        let source_file = Path::new(SOURCE_DIR_NAME).join(&file).with_extension(SOURCE_FILE_EXT);
+
+        let source = if let Ok (s) = fs::read_to_string(root.join(&source_file)) {
+            s
+        } else {
+            continue;
+        };
+
+        // Drafts are not built, so they cannot be referred to
+        let (front_matter, body, line) = if let BlogResult::Ok (s) = FrontMatter::split(&source, &source_file) {
+            s
+        } else {
+            continue;
+        };
+        if front_matter.draft {
+            continue;
+        }
+
+        // Parse the page, including any included files
+        let mut tokenizer = Tokenizer::from_line(body.to_owned(), line);
+        let mut nodes = parser.parse(&mut tokenizer);
+        let mut stack = vec![fs::canonicalize(root.join(&source_file)).unwrap_or(source_file.clone())];
+        let _ = resolve(&mut nodes, parser, root, config, &mut stack);
+
+        for (label, text) in number(&mut nodes, config) {
+            targets.entry(label.to_owned()).or_insert_with(|| (text, href(&file, &label)));
+        }
+    }
+
+    targets
+}
+
+/// Construct the URI of a label on a page.
+/// 
+/// # Parameters
+/// - `file` (`&Path`): the file stem of the page, relative to the
+///   source directory
+/// - `label` (`&str`): the label
+/// 
+/// # Returns
+/// A `String` containing the URI.
+fn href(file: &Path, label: &str) -> String {
+    let path = file.with_extension(OUTPUT_FILE_EXT)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<String>>()
+        .join("/");
+
+    format!("/{}#{}", path, label)
+}
@@ -6,6 +6,8 @@
 // Enforce all documentation.
 #![deny(missing_docs)]
 
+mod template;
+
 use std::{
     fs,
     path::Path,
@@ -13,21 +15,33 @@ use std::{
 
 use blog_cfg::{
     Config,
+    FrontMatter,
+    MathRender,
+    NoteStyle,
     SiteStyle,
 };
 
 use blog_err::{
     BlogResult,
+    unwrap_or_return,
     unwrap_result,
+    unwrap_result_or_return,
 };
 
 use blog_env::{
+    DEFAULT_LAYOUT_NAME,
+    LAYOUT_DIR_NAME,
+    OUTPUT_FILE_EXT,
     SOURCE_DIR_NAME,
     STYLESHEET_FILE_NAME,
     INDEX_PAGE_NAME,
 };
 
-use blog_prs::Expression;
+use blog_prs::{
+    Expression,
+    escape,
+    escape_uri,
+};
 
 use blog_sty::links;
 
@@ -36,6 +50,12 @@ use convert_case::{
     Casing,
 };
 
+pub use template::{
+    Context,
+    Template,
+    Value,
+};
+
 /// An HTML emitter that takes in a list of expressions and returns
 /// HTML source code.
 pub struct Emitter {
@@ -59,21 +79,28 @@ impl Emitter {
     }
 
     /// Emit HTML code from a list of expressions.
+    /// 
+    /// The page is displayed using the layout named in its front matter,
+    /// or the default layout if it has none.
     ///
     /// # Parameters
     /// - `expressions` (`Vec<Expression>`): the list of expressions
     /// - `root` (`&Path`): the location of the site root
     /// - `filename` (`&Path`): the file stem of the output HTML
+    /// - `front_matter` (`&FrontMatter`): the front matter of the page
     /// 
     /// # Returns
     /// A `String` containing HTML.
-    pub fn emit(&self, expressions: Vec<Expression>, root: &Path, filename: &Path) -> BlogResult<String> {
-        // Open document and head
-        let mut output = String::from("<!DOCTYPE html>\n<html>\n\n<head>\n\n");
-
+    pub fn emit(&self, mut expressions: Vec<Expression>, root: &Path, filename: &Path, front_matter: &FrontMatter) -> BlogResult<String> {
         // Initialize result
         let mut result = BlogResult::default();
 
+        // Find the layout before doing any work
+        let layout = unwrap_or_return!(self.layout(root, front_matter));
+
+        // Initialize head
+        let mut head = String::new();
+
         // Add analytics tag
         if let Some (a) = &self.config.analytics {
             // Get tag path
@@ -86,24 +113,38 @@ impl Emitter {
                 &format!("could not read analytics tag '{}'", analytics_tag_path.display())
             );
 
-            output.push_str(&format!("{}\n\n", analytics));
+            head.push_str(&format!("{}\n\n", analytics));
         }
 
         // Construct title
         let filename_str: &str = filename.file_name().unwrap().to_str().unwrap();
-        let page_title = &filename_str.to_case(Case::Title);
+        let page_title = &front_matter.title.to_owned()
+            .unwrap_or_else(|| filename_str.to_case(Case::Title));
 
         // Remove "Index" from `index.html`
-        if page_title == INDEX_PAGE_NAME {
-            output.push_str(&format!("<title>{}</title>\n\n", self.config.site.name));
+        if front_matter.title.is_none() && page_title == INDEX_PAGE_NAME {
+            head.push_str(&format!("<title>{}</title>\n\n", escape(&self.config.site.name)));
         } else {
-            output.push_str(&format!("<title>{} | {}</title>\n\n", page_title, self.config.site.name));
+            head.push_str(&format!("<title>{} | {}</title>\n\n", escape(page_title), escape(&self.config.site.name)));
         }
         
+        // Add metadata from front matter
+        if let Some (d) = &front_matter.description {
+            head.push_str(&format!("<meta name=\"description\" content=\"{}\">\n\n", escape(d)));
+        }
+
+        if let Some (a) = &front_matter.author {
+            head.push_str(&format!("<meta name=\"author\" content=\"{}\">\n\n", escape(a)));
+        }
+
+        if !front_matter.tags.is_empty() {
+            head.push_str(&format!("<meta name=\"keywords\" content=\"{}\">\n\n", escape(&front_matter.tags.join(", "))));
+        }
+
         // Add links to stylesheet and fonts
         if let Some (s) = &self.config.site.style {
             // Link stylesheet
-            output.push_str(&format!("<link rel=\"stylesheet\" href=\"/{}\">\n\n", STYLESHEET_FILE_NAME));
+            head.push_str(&format!("<link rel=\"stylesheet\" href=\"/{}\">\n\n", STYLESHEET_FILE_NAME));
 
             // Link fonts
             use SiteStyle::*;
@@ -112,52 +153,357 @@ impl Emitter {
                 Book => links::BOOK,
                 Linotype => links::LINOTYPE,
             };
-            output.push_str(&format!("{}\n\n", links));
+            head.push_str(&format!("{}\n\n", links));
+
+            // Link MathJax, if any equations are left to display
+            if self.mathjax(&expressions) {
+                head.push_str(&format!("{}\n\n", links::MATHJAX));
+            }
         }
 
         // Add favicon
         if let Some (f) = &self.config.site.icon {
             // Link favicon
-            output.push_str(&format!("<link rel=\"icon\" type=\"image/x-icon\" href=\"/{}\">\n\n", f));
+            head.push_str(&format!("<link rel=\"icon\" type=\"image/x-icon\" href=\"/{}\">\n\n", escape_uri(f)));
+        }
+
+        // Collect footnote definitions, in order
+        let mut footnotes = Vec::new();
+        for expression in &expressions {
+            expression.walk(&mut |expr| if let Expression::Footnote { number, text, .. } = expr {
+                // Unreferenced footnotes are never displayed
+                if *number != 0 {
+                    footnotes.push((*number, text.clone()));
+                }
+            });
         }
+        footnotes.sort_by_key(|(n, _)| *n);
 
-        // Close head and open body
-        output.push_str("</head>\n\n<body>\n\n");
+        // A file included more than once defines its footnotes more than once
+        footnotes.dedup_by_key(|(n, _)| *n);
+
+        // Only some styles have room for notes in the margin
+        let sidenotes = self.config.site.notes == Some (NoteStyle::Sidenotes)
+            && matches!(self.config.site.style, Some (SiteStyle::Book) | Some (SiteStyle::Linotype));
+
+        if sidenotes {
+            place_sidenotes(&mut expressions, &footnotes);
+        }
+
+        // Link each header to itself, if requested
+        if self.config.site.permalinks == Some (true) {
+            for expression in expressions.iter_mut() {
+                expression.walk_mut(&mut |expr| {
+                    use Expression::*;
+                    if let H1 { permalink, .. }
+                        | H2 { permalink, .. }
+                        | H3 { permalink, .. }
+                        | H4 { permalink, .. }
+                        | H5 { permalink, .. }
+                        | H6 { permalink, .. } = expr
+                    {
+                        *permalink = true;
+                    }
+                });
+            }
+        }
+
+        // Generate a menu, if one is available in TOML
+        let menu = self.menu();
+
+        // Initialize content
+        let mut content = String::new();
 
         // Emit each expression
         for expression in expressions {
             if expression == Expression::Menu {
-                // Generate a menu
-                if let Some (m) = &self.config.menu {
-                    // Open a new DIV
-                    let mut menu = String::from("<div class=\"menu\">\n\n");
-
-                    // Emit menu based on config info
-                    for (text, href) in m.names.iter().zip(&m.links) {
-                        menu.push_str(&format!("<a href=\"{}\">{}</a>\n\n", href, text));
-                    }
+                // Insert the menu from TOML, which is empty if there is none
+                content.push_str(&menu);
+            } else {
+                // Output expression as normal
+                content.push_str(&expression.html(true));
+            }
+        }
+
+        // Add footnotes to the end of the page
+        if !sidenotes && !footnotes.is_empty() {
+            content.push_str("<section class=\"footnotes\">\n\n<hr>\n\n<ol>\n\n");
 
-                    // Close DIV
-                    menu.push_str("</div>\n\n");
+            for (number, text) in &footnotes {
+                let mut note = String::new();
 
-                    // Concatenante menu to output
-                    output.push_str(&menu);
-                } else {
-                    // No menu available in TOML, just skip for now
-                    continue;
+                // Format each interior expression
+                for expr in text {
+                    note.push_str(&expr.html(false));
                 }
-            } else {
-                // Output expression as normal
-                output.push_str(&expression.html(true));
+
+                content.push_str(&format!(
+                    "<li id=\"fn-{}\">{} <a class=\"footnote-backref\" href=\"#fnref-{}\">&#x21A9;</a></li>\n\n",
+                    number,
+                    note,
+                    number,
+                ));
             }
+
+            content.push_str("</ol>\n\n</section>\n\n");
         }
 
-        // Close body and document
-        output.push_str("</body>\n\n</html>");
+        let output = if let Some (t) = layout {
+            // Make the page available to the layout
+            let mut context = Context::default();
+            context.insert("title", Value::Text (page_title.to_owned()));
+            context.insert("head", Value::Html (head));
+            context.insert("menu", Value::Html (menu));
+            context.insert("content", Value::Html (content));
+            self.site_context(&mut context);
+            page_context(&mut context, front_matter);
+
+            t.render(&context)
+        } else {
+            // Use the built-in layout
+            format!("<!DOCTYPE html>\n<html>\n\n<head>\n\n{}</head>\n\n<body>\n\n{}</body>\n\n</html>", head, content)
+        };
 
         match result {
             BlogResult::Ok (_) => result.ok(output),
             BlogResult::Err (_) => result,
         }
     }
+
+    /// Check if a page needs MathJax to display its equations.
+    /// 
+    /// When equations are rendered to MathML, only those that could not
+    /// be rendered are left as text for MathJax.
+    /// 
+    /// # Parameters
+    /// - `expressions` (`&[Expression]`): the expressions on the page
+    /// 
+    /// # Returns
+    /// A `bool` indicating if the page needs MathJax.
+    fn mathjax(&self, expressions: &[Expression]) -> bool {
+        let render = self.config.math.as_ref().and_then(|m| m.render);
+        if render != Some (MathRender::Mathml) {
+            return true;
+        }
+
+        let mut unrendered = false;
+        for expression in expressions {
+            expression.walk(&mut |expr| if let Expression::Text (t) = expr {
+                unrendered |= t.contains("\\(") || t.contains("\\[");
+            });
+        }
+
+        unrendered
+    }
+
+    /// Find and parse the layout for a page.
+    /// 
+    /// # Parameters
+    /// - `root` (`&Path`): the location of the site root
+    /// - `front_matter` (`&FrontMatter`): the front matter of the page
+    /// 
+    /// # Returns
+    /// A `BlogResult<Option<Template>>` containing the layout, or `None`
+    /// if the page uses the built-in layout.
+    fn layout(&self, root: &Path, front_matter: &FrontMatter) -> BlogResult<Option<Template>> {
+        let result = BlogResult::default();
+
+        // Construct the path of the layout, relative to the site root
+        let name = front_matter.template.as_deref().unwrap_or(DEFAULT_LAYOUT_NAME);
+        let layout_file = Path::new(SOURCE_DIR_NAME)
+            .join(LAYOUT_DIR_NAME)
+            .join(name)
+            .with_extension(OUTPUT_FILE_EXT);
+
+        // Only layouts named by the page are required to exist
+        if front_matter.template.is_none() && !root.join(&layout_file).exists() {
+            return result.ok(None);
+        }
+
+        // Read the layout
+        let source = unwrap_result_or_return!(
+            fs::read_to_string(root.join(&layout_file)),
+            &format!("could not read layout '{}'", layout_file.display())
+        );
+
+        let template = unwrap_or_return!(Template::parse(&source, &layout_file));
+
+        result.ok(Some (template))
+    }
+
+    /// Generate the site menu.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `String` containing HTML, or an empty string if there is
+    /// no menu available in TOML.
+    fn menu(&self) -> String {
+        let m = if let Some (m) = &self.config.menu {
+            m
+        } else {
+            return String::new();
+        };
+
+        // Open a new DIV
+        let mut menu = String::from("<div class=\"menu\">\n\n");
+
+        // Emit menu based on config info
+        for (text, href) in m.names.iter().zip(&m.links) {
+            menu.push_str(&format!("<a href=\"{}\">{}</a>\n\n", escape_uri(href), escape(text)));
+        }
+
+        // Close DIV
+        menu.push_str("</div>\n\n");
+
+        menu
+    }
+
+    /// Make the site configuration available to a layout.
+    /// 
+    /// # Parameters
+    /// - `context` (`&mut Context`): the values available to the layout
+    /// 
+    /// # Returns
+    /// None.
+    fn site_context(&self, context: &mut Context) {
+        let site = &self.config.site;
+
+        context.insert("site.name", Value::Text (site.name.to_owned()));
+
+        if let Some (i) = &site.icon {
+            context.insert("site.icon", Value::Text (i.to_owned()));
+        }
+
+        if let Some (s) = &site.style {
+            context.insert("site.style", Value::Text (s.to_string()));
+        }
+    }
+}
+
+/// Make the front matter of a page available to a layout.
+/// 
+/// Every key of the front matter is available as `page.key`.
+/// 
+/// # Parameters
+/// - `context` (`&mut Context`): the values available to the layout
+/// - `front_matter` (`&FrontMatter`): the front matter of the page
+/// 
+/// # Returns
+/// None.
+fn page_context(context: &mut Context, front_matter: &FrontMatter) {
+    // Keys not known to the Blog Builder
+    for (k, v) in &front_matter.extra {
+        context.insert_toml(&format!("page.{}", k), v);
+    }
+
+    let text = [
+        ("page.title", &front_matter.title),
+        ("page.description", &front_matter.description),
+        ("page.date", &front_matter.date),
+        ("page.author", &front_matter.author),
+        ("page.template", &front_matter.template),
+    ];
+
+    for (name, value) in text {
+        if let Some (v) = value {
+            context.insert(name, Value::Text (v.to_owned()));
+        }
+    }
+
+    let tags = front_matter.tags.iter()
+        .map(|t| Value::Text (t.to_owned()))
+        .collect();
+
+    context.insert("page.tags", Value::List (tags));
+    context.insert("page.draft", Value::Bool (front_matter.draft));
+}
+
+/// Replace each footnote reference with a sidenote.
+/// 
+/// # Parameters
+/// - `expressions` (`&mut [Expression]`): the list of expressions
+/// - `footnotes` (`&[(usize, Vec<Expression>)]`): the number and text
+///   of each footnote
+/// 
+/// # Returns
+/// None.
+fn place_sidenotes(expressions: &mut [Expression], footnotes: &[(usize, Vec<Expression>)]) {
+    for expression in expressions {
+        expression.walk_mut(&mut |expr| if let Expression::FootnoteRef { number, .. } = expr {
+            let text = if let Some ((_, t)) = footnotes.iter().find(|(n, _)| n == number) {
+                t
+            } else {
+                // Undefined footnotes are reported by the checker
+                return;
+            };
+
+            // Drop references nested inside of the note itself,
+            //  since they would have nowhere to go
+            let mut text = text.clone();
+            for e in text.iter_mut() {
+                e.walk_mut(&mut |e| if let Expression::FootnoteRef { .. } = e {
+                    *e = Expression::Text (String::new());
+                });
+            }
+
+            *expr = Expression::Sidenote {
+                number: *number,
+                text,
+            };
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blog_cfg::{
+        Config,
+        MathConfig,
+        MathRender,
+    };
+
+    use blog_prs::{
+        Expression,
+        Parser,
+        render_equations,
+    };
+
+    use blog_tkn::Tokenizer;
+
+    use super::Emitter;
+
+    /// Parse a page and render its equations to MathML where possible.
+    fn render_page(source: &str) -> Vec<Expression> {
+        let mut nodes = Parser::new().parse(&mut Tokenizer::from(source.to_owned()));
+        render_equations(&mut nodes, &blog_mth::render);
+
+        nodes.into_iter().map(|n| n.expression).collect()
+    }
+
+    #[test]
+    fn mathjax_is_linked_only_for_unrendered_equations() {
+        let config = Config {
+            math: Some (MathConfig {
+                render: Some (MathRender::Mathml),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let emitter = Emitter::new(&config);
+
+        let rendered = render_page("Euler: \\(e^{i\\pi} + 1 = 0\\)\n");
+        assert!(rendered[0].html(true).contains("<math>"));
+        assert!(!emitter.mathjax(&rendered));
+
+        // Unsupported equations are left as written, for MathJax
+        let mixed = render_page("Both \\(x^2\\) and \\(\\color{red}{x}\\)\n");
+        let html = mixed[0].html(true);
+        assert!(html.contains("<math>") && html.contains("\\(\\color{red}{x}\\)"), "{}", html);
+        assert!(emitter.mathjax(&mixed));
+
+        // Without rendering, MathJax displays every equation
+        assert!(Emitter::new(&Config::default()).mathjax(&rendered));
+    }
 }
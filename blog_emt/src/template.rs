@@ -0,0 +1,401 @@
+//! Layout templates for the Blog Builder.
+//! 
+//! Templates are HTML files containing placeholders, conditionals,
+//! and loops.
+//! 
+//! - `{{ name }}` is replaced by the value of `name`
+//! - `{% if name %} ... {% else %} ... {% endif %}` includes its body
+//!   only if `name` is set, true, and not empty
+//! - `{% for item in name %} ... {% endfor %}` repeats its body for
+//!   each item in the list `name`
+
+use std::{
+    collections::HashMap,
+    path::Path,
+};
+
+use blog_err::{
+    BlogError,
+    BlogErrorLocation,
+    BlogResult,
+};
+
+use blog_prs::escape;
+
+#[derive(Clone, Debug)]
+/// A value available to a template.
+pub enum Value {
+    /// Text, escaped when displayed.
+    Text (String),
+
+    /// HTML, displayed as written.
+    Html (String),
+
+    /// Boolean.
+    Bool (bool),
+
+    /// List of values.
+    List (Vec<Value>),
+}
+
+impl Value {
+    /// Convert a TOML value into a template value.
+    /// 
+    /// # Parameters
+    /// - `value` (`&toml::Value`): the TOML value
+    /// 
+    /// # Returns
+    /// A new `Value`.
+    pub fn from_toml(value: &toml::Value) -> Self {
+        use toml::Value::*;
+        match value {
+            String (s) => Value::Text (s.to_owned()),
+            Boolean (b) => Value::Bool (*b),
+            Array (a) => Value::List (a.iter().map(Value::from_toml).collect()),
+            Integer (_) | Float (_) | Datetime (_) | Table (_) => Value::Text (value.to_string()),
+        }
+    }
+
+    /// Is this value set, true, and not empty?
+    fn truthy(&self) -> bool {
+        use Value::*;
+        match self {
+            Text (s) | Html (s) => !s.is_empty(),
+            Bool (b) => *b,
+            List (l) => !l.is_empty(),
+        }
+    }
+
+    /// Display this value as HTML.
+    fn render(&self) -> String {
+        use Value::*;
+        match self {
+            Text (s) => escape(s),
+            Html (s) => s.to_owned(),
+            Bool (b) => b.to_string(),
+            List (l) => l.iter().map(|v| v.render()).collect::<Vec<String>>().join(", "),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+/// Values available to a template, by name.
+pub struct Context {
+    /// Values by name.
+    values: HashMap<String, Value>,
+}
+
+impl Context {
+    /// Set the value of a name.
+    /// 
+    /// # Parameters
+    /// - `name` (`&str`): the name
+    /// - `value` (`Value`): the value
+    /// 
+    /// # Returns
+    /// None.
+    pub fn insert(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_owned(), value);
+    }
+
+    /// Set the value of a name from TOML.
+    /// 
+    /// The keys of a TOML table are available as `name.key`.
+    /// 
+    /// # Parameters
+    /// - `name` (`&str`): the name
+    /// - `value` (`&toml::Value`): the value
+    /// 
+    /// # Returns
+    /// None.
+    pub fn insert_toml(&mut self, name: &str, value: &toml::Value) {
+        if let toml::Value::Table (t) = value {
+            for (k, v) in t {
+                self.insert_toml(&format!("{}.{}", name, k), v);
+            }
+        }
+
+        self.insert(name, Value::from_toml(value));
+    }
+}
+
+#[derive(Debug)]
+/// A piece of a template.
+enum Node {
+    /// Text, displayed as written.
+    Text (String),
+
+    /// Placeholder.
+    Var (String),
+
+    /// Conditional.
+    If {
+        /// Name of the condition.
+        name: String,
+
+        /// Displayed if the condition holds.
+        then: Vec<Node>,
+
+        /// Displayed otherwise.
+        otherwise: Vec<Node>,
+    },
+
+    /// Loop.
+    For {
+        /// Name of each item.
+        item: String,
+
+        /// Name of the list.
+        list: String,
+
+        /// Displayed once for each item.
+        body: Vec<Node>,
+    },
+}
+
+#[derive(Debug)]
+/// A piece of a template, before nesting.
+enum Piece {
+    /// Text, displayed as written.
+    Text (String),
+
+    /// Placeholder.
+    Var (String),
+
+    /// Tag, split into words.
+    Tag (Vec<String>),
+}
+
+#[derive(Default, Debug)]
+/// A layout template.
+pub struct Template {
+    /// Pieces of the template.
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parse a template.
+    /// 
+    /// # Parameters
+    /// - `source` (`&str`): the source of the template
+    /// - `filename` (`&Path`): the path of the template, for
+    ///   error messages
+    /// 
+    /// # Returns
+    /// A `BlogResult<Template>` containing the template or any errors.
+    pub fn parse(source: &str, filename: &Path) -> BlogResult<Template> {
+        let result = BlogResult::default();
+
+        // Construct an error at a range of the source
+        let error = |message: &str, start: usize, end: usize, hint: Option<&str>| {
+            let location = BlogErrorLocation::from_offsets(source, start, end);
+
+            BlogError::ParseError {
+                message: message.to_string(),
+                filename: filename.to_owned(),
+                excerpt: location.excerpt(source),
+                hint: hint.map(|h| h.to_string()),
+                location,
+            }
+        };
+
+        // Split the template into pieces
+        let mut pieces = Vec::new();
+        let mut rest = 0;
+        while let Some (i) = source[rest..].find('{') {
+            let start = rest + i;
+
+            // Find the end of the placeholder or tag
+            let close = match source.get(start..start + 2) {
+                Some ("{{") => "}}",
+                Some ("{%") => "%}",
+                _ => {
+                    pieces.push((Piece::Text (source[rest..=start].to_owned()), rest));
+                    rest = start + 1;
+                    continue;
+                },
+            };
+
+            let end = if let Some (j) = source[start + 2..].find(close) {
+                start + 2 + j
+            } else {
+                return result.err(error(
+                    &format!("unclosed '{}'", &source[start..start + 2]),
+                    start,
+                    start + 2,
+                    Some (&format!("close it with '{}'", close)),
+                ));
+            };
+
+            if start > rest {
+                pieces.push((Piece::Text (source[rest..start].to_owned()), rest));
+            }
+
+            let inner = source[start + 2..end].trim();
+            let piece = if close == "}}" {
+                Piece::Var (inner.to_owned())
+            } else {
+                Piece::Tag (inner.split_whitespace().map(|w| w.to_owned()).collect())
+            };
+
+            pieces.push((piece, start));
+            rest = end + 2;
+        }
+
+        if rest < source.len() {
+            pieces.push((Piece::Text (source[rest..].to_owned()), rest));
+        }
+
+        // Nest the pieces
+        let mut index = 0;
+        match nest(&pieces, &mut index) {
+            Ok ((nodes, None)) => result.ok(Template {
+                nodes,
+            }),
+            Ok ((_, Some (tag))) => {
+                let start = pieces[index - 1].1;
+                result.err(error(&format!("unexpected '{{% {} %}}'", tag), start, start + 2, None))
+            },
+            Err ((message, start, hint)) => result.err(error(&message, start, start + 2, hint)),
+        }
+    }
+
+    /// Display this template with a given set of values.
+    /// 
+    /// Placeholders whose names are not set are displayed as
+    /// empty strings.
+    /// 
+    /// # Parameters
+    /// - `context` (`&Context`): the values
+    /// 
+    /// # Returns
+    /// A `String` containing HTML.
+    pub fn render(&self, context: &Context) -> String {
+        let mut output = String::new();
+        render(&self.nodes, context, &mut Vec::new(), &mut output);
+        output
+    }
+}
+
+/// Nest a list of template pieces into nodes.
+/// 
+/// # Parameters
+/// - `pieces` (`&[(Piece, usize)]`): the pieces and their byte offsets
+/// - `index` (`&mut usize`): the index of the next piece
+/// 
+/// # Returns
+/// A `Result` containing the nodes and the name of the tag that
+/// ended them (if any), or an error message, its byte offset, and
+/// a hint.
+#[allow(clippy::type_complexity)]
+fn nest(pieces: &[(Piece, usize)], index: &mut usize) -> Result<(Vec<Node>, Option<String>), (String, usize, Option<&'static str>)> {
+    let mut nodes = Vec::new();
+
+    while let Some ((piece, start)) = pieces.get(*index) {
+        *index += 1;
+
+        let words = match piece {
+            Piece::Text (t) => {
+                nodes.push(Node::Text (t.to_owned()));
+                continue;
+            },
+            Piece::Var (v) => {
+                nodes.push(Node::Var (v.to_owned()));
+                continue;
+            },
+            Piece::Tag (w) => w.iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+        };
+
+        match words.as_slice() {
+            ["if", name] => {
+                let (then, end) = nest(pieces, index)?;
+
+                let otherwise = match end.as_deref() {
+                    Some ("else") => match nest(pieces, index)? {
+                        (o, Some (e)) if e == "endif" => o,
+                        _ => return Err (("unclosed '{% if %}'".to_string(), *start, Some ("close it with '{% endif %}'"))),
+                    },
+                    Some ("endif") => Vec::new(),
+                    _ => return Err (("unclosed '{% if %}'".to_string(), *start, Some ("close it with '{% endif %}'"))),
+                };
+
+                nodes.push(Node::If {
+                    name: name.to_string(),
+                    then,
+                    otherwise,
+                });
+            },
+            ["for", item, "in", list] => {
+                let body = match nest(pieces, index)? {
+                    (b, Some (e)) if e == "endfor" => b,
+                    _ => return Err (("unclosed '{% for %}'".to_string(), *start, Some ("close it with '{% endfor %}'"))),
+                };
+
+                nodes.push(Node::For {
+                    item: item.to_string(),
+                    list: list.to_string(),
+                    body,
+                });
+            },
+
+            // These end the enclosing tag
+            ["else"] | ["endif"] | ["endfor"] => return Ok ((nodes, Some (words[0].to_string()))),
+
+            _ => return Err ((
+                format!("unrecognized tag '{}'", words.join(" ")),
+                *start,
+                Some ("the available tags are `if`, `else`, `endif`, `for`, and `endfor`"),
+            )),
+        }
+    }
+
+    Ok ((nodes, None))
+}
+
+/// Display a list of template nodes.
+/// 
+/// # Parameters
+/// - `nodes` (`&[Node]`): the nodes
+/// - `context` (`&Context`): the values
+/// - `items` (`&mut Vec<(String, Value)>`): the loop items in scope
+/// - `output` (`&mut String`): the output
+/// 
+/// # Returns
+/// None.
+fn render(nodes: &[Node], context: &Context, items: &mut Vec<(String, Value)>, output: &mut String) {
+    // Look up a name, starting with the innermost loop item
+    let get = |name: &str, items: &[(String, Value)]| items.iter()
+        .rev()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.clone())
+        .or_else(|| context.values.get(name).cloned());
+
+    for node in nodes {
+        match node {
+            Node::Text (t) => output.push_str(t),
+            Node::Var (name) => if let Some (v) = get(name, items) {
+                output.push_str(&v.render());
+            },
+            Node::If {
+                name,
+                then,
+                otherwise,
+            } => if get(name, items).is_some_and(|v| v.truthy()) {
+                render(then, context, items, output);
+            } else {
+                render(otherwise, context, items, output);
+            },
+            Node::For {
+                item,
+                list,
+                body,
+            } => if let Some (Value::List (l)) = get(list, items) {
+                for v in l {
+                    items.push((item.to_owned(), v));
+                    render(body, context, items, output);
+                    items.pop();
+                }
+            },
+        }
+    }
+}
@@ -42,6 +42,50 @@ pub const INDEX_FILE_NAME: &str = "index.md";
 /// example, "My Website").
 pub const INDEX_PAGE_NAME: &str = "Index";
 
+/// Layout subdirectory name.
+/// 
+/// Layouts are stored in this subdirectory of the source
+/// directory.
+pub const LAYOUT_DIR_NAME: &str = "_layouts";
+
+/// Default layout name.
+/// 
+/// Pages that do not name a layout in their front matter use
+/// this layout, if it exists.
+pub const DEFAULT_LAYOUT_NAME: &str = "default";
+
+/// Maximum include depth.
+/// 
+/// Source files may include other source files, which may
+/// themselves include other source files, up to this depth.
+pub const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Built-in control sequence names.
+/// 
+/// Sites may not define control sequences with these names
+/// in the configuration file.
+pub const BUILTIN_CONTROLS: &[&str] = &[
+    "byline",
+    "end",
+    "endmath",
+    "float",
+    "html",
+    "image",
+    "include",
+    "mathblock",
+    "notice",
+    "qed",
+    "ref",
+    "tile",
+    "toc",
+];
+
+/// Front matter delimiter.
+/// 
+/// Front matter is written in TOML between two lines
+/// containing only this delimiter, at the top of a source file.
+pub const FRONT_MATTER_DELIMITER: &str = "+++";
+
 /// Stylesheet name.
 pub const STYLESHEET_FILE_NAME: &str = "style.css";
 
@@ -7,10 +7,15 @@ use std::{
         Debug,
         Display
     },
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
     process,
 };
 
+use colored::*;
+
 use blog_env::CONFIG_FILE_NAME;
 
 use crate::BlogErrorLocation;
@@ -36,7 +41,25 @@ pub enum BlogError {
 
         /// Location of error.
         location: BlogErrorLocation,
-    }
+
+        /// Line of source code containing the error.
+        excerpt: String,
+
+        /// Suggestion for fixing the error, if available.
+        hint: Option<String>,
+    },
+
+    /// Error occurred in reading the configuration file.
+    ConfigError {
+        /// Error message from configuration parser.
+        message: String,
+
+        /// Location of error.
+        location: BlogErrorLocation,
+
+        /// Line of the configuration file containing the error.
+        excerpt: String,
+    },
 }
 
 impl BlogError {
@@ -47,6 +70,74 @@ impl BlogError {
         // Exit
         process::exit(1);
     }
+
+    /// Get a short summary of this error, to be displayed above
+    /// its source excerpt.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `String` containing the summary.
+    pub fn summary(&self) -> String {
+        use BlogError::*;
+        match self {
+            ParseError {
+                message,
+                ..
+            } => message.to_owned(),
+            ConfigError {
+                message,
+                ..
+            } => format!("invalid configuration file: {}", message),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Get the source code associated with this error.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// An `Option` containing the filename, the location of the error,
+    /// and the line of source code containing the error, if available.
+    pub fn excerpt(&self) -> Option<(&Path, &BlogErrorLocation, &str)> {
+        use BlogError::*;
+        match self {
+            ParseError {
+                filename,
+                location,
+                excerpt,
+                ..
+            } => Some ((filename, location, excerpt)),
+            ConfigError {
+                location,
+                excerpt,
+                ..
+            } => Some ((Path::new(CONFIG_FILE_NAME), location, excerpt)),
+            _ => None,
+        }
+    }
+
+    /// Get a suggestion for fixing this error.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// An `Option<String>` containing the hint, if available.
+    pub fn hint(&self) -> Option<String> {
+        use BlogError::*;
+        match self {
+            CouldNotFindRoot => Some ("create a new site with `blog new <NAME>`".to_string()),
+            ParseError {
+                hint,
+                ..
+            } => hint.to_owned(),
+            _ => None,
+        }
+    }
 }
 
 impl Error for BlogError { }
@@ -68,14 +159,23 @@ impl Display for BlogError {
                 message,
                 filename,
                 location,
+                ..
             } => &format!(
-                "could not parse file '{}': {} ({})",
-                filename.display(),
+                "could not parse file '{}': {}",
+                format!("{}:{}", filename.display(), location).bold().bright_blue(),
+                message,
+            ),
+            ConfigError {
                 message,
                 location,
+                ..
+            } => &format!(
+                "could not read file '{}': {}",
+                format!("{}:{}", CONFIG_FILE_NAME, location).bold().bright_blue(),
+                message,
             ),
         };
 
         write!(f, "{}", err)
     }
-}
\ No newline at end of file
+}
@@ -8,10 +8,13 @@
 
 mod error;
 mod location;
+mod render;
 mod result;
 
 pub use error::BlogError;
 
 pub use location::BlogErrorLocation;
 
+pub use render::render;
+
 pub use result::BlogResult;
\ No newline at end of file
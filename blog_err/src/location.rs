@@ -5,25 +5,61 @@ use std::fmt::{
     Display,
 };
 
-use colored::*;
-
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
 /// Location that an error occurred.
-pub enum BlogErrorLocation {
-    /// Error occurred at the beginning of a file.
-    Beginning,
+pub struct BlogErrorLocation {
+    /// Line number (starting from 1).
+    pub line: usize,
+
+    /// Column number (starting from 1).
+    pub column: usize,
 
-    /// Error occurred after the contained sequence.
-    After (String),
+    /// Length of the erroneous sequence in characters.
+    pub length: usize,
+}
+
+impl BlogErrorLocation {
+    /// Construct a location from a range of byte offsets into a source.
+    /// 
+    /// # Parameters
+    /// - `source` (`&str`): the source code
+    /// - `start` (`usize`): the byte offset of the start of the sequence
+    /// - `end` (`usize`): the byte offset of the end of the sequence
+    /// 
+    /// # Returns
+    /// A new `BlogErrorLocation`.
+    pub fn from_offsets(source: &str, start: usize, end: usize) -> Self {
+        // Everything before the erroneous sequence
+        let before = source.get(..start).unwrap_or(source);
+
+        // The erroneous sequence itself
+        let sequence = source.get(start..end).unwrap_or_default();
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before.rsplit('\n').next().unwrap_or_default().chars().count() + 1,
+            length: sequence.chars().count(),
+        }
+    }
+
+    /// Get the line of source code containing this location.
+    /// 
+    /// # Parameters
+    /// - `source` (`&str`): the source code
+    /// 
+    /// # Returns
+    /// A `String` containing the line, or an empty string if the line
+    /// does not exist.
+    pub fn excerpt(&self, source: &str) -> String {
+        source.lines()
+            .nth(self.line.saturating_sub(1))
+            .unwrap_or_default()
+            .to_string()
+    }
 }
 
 impl Display for BlogErrorLocation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use BlogErrorLocation::*;
-        let output = match self {
-            Beginning => "at beginning of file",
-            After (s) => &format!("after expression '{}'", s.bold().bright_blue()),
-        };
-
-        write!(f, "{}", output)
+        write!(f, "{}:{}", self.line, self.column)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,74 @@
+//! Error rendering for the Blog Builder.
+
+use anyhow::Error as AnyError;
+
+use colored::*;
+
+use crate::BlogError;
+
+/// Render an error for display to the user.
+/// 
+/// Errors that carry a location are displayed with the offending line
+/// of source code, underlined at the location of the error.
+/// 
+/// # Parameters
+/// - `error` (`&anyhow::Error`): the error
+/// 
+/// # Returns
+/// A `String` containing the rendered error.
+pub fn render(error: &AnyError) -> String {
+    let label = format!("{:>12}", "Error").bold().bright_red();
+
+    // Only Blog Builder errors carry excerpts and hints
+    let error = if let Some (e) = error.downcast_ref::<BlogError>() {
+        e
+    } else {
+        return format!("{} {}", label, error);
+    };
+
+    // Width of the line number gutter
+    let width = error.excerpt()
+        .map(|(_, location, _)| location.line.to_string().len())
+        .unwrap_or_default();
+
+    let mut output = match error.excerpt() {
+        Some ((filename, location, excerpt)) => {
+            // Preserve tabs so that the underline lines up with the excerpt
+            let indent = excerpt.chars()
+                .take(location.column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+
+            // Underline at least one character, but never past the end of the line
+            let remaining = excerpt.chars().count().saturating_sub(location.column.saturating_sub(1));
+            let underline = "^".repeat(location.length.min(remaining).max(1));
+
+            format!(
+                "{} {}\n{:>12} {}:{}\n{:>12} {:>width$} |\n{:>12} {} | {}\n{:>12} {:>width$} | {}{}",
+                label,
+                error.summary().bold(),
+                "-->".bright_blue(),
+                filename.display(),
+                location,
+                "",
+                "",
+                "",
+                location.line.to_string().bright_blue(),
+                excerpt,
+                "",
+                "",
+                indent,
+                underline.bold().bright_red(),
+                width = width,
+            )
+        },
+        None => format!("{} {}", label, error),
+    };
+
+    // Append a hint, if one is available
+    if let Some (hint) = error.hint() {
+        output.push_str(&format!("\n{:>12} {:>width$} = {}: {}", "", "", "hint".bold(), hint, width = width));
+    }
+
+    output
+}
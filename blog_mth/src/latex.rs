@@ -0,0 +1,755 @@
+//! Conversion of LaTeX equations into MathML.
+
+use crate::symbols::{
+    self,
+    Kind,
+};
+
+/// Operators that do not stretch unless placed after `\left` or
+/// `\right`.
+const FENCES: &[&str] = &["(", ")", "[", "]", "{", "}", "|", "‖", "⟨", "⟩", "⌊", "⌋", "⌈", "⌉", "/"];
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Tokens of a LaTeX equation.
+enum Token {
+    /// Single character.
+    Char (char),
+
+    /// Command, without its backslash.
+    Command (String),
+
+    /// Opening brace.
+    Open,
+
+    /// Closing brace.
+    Close,
+
+    /// Superscript.
+    Sup,
+
+    /// Subscript.
+    Sub,
+
+    /// Column separator.
+    Amp,
+}
+
+/// An element of an equation, before its scripts are attached.
+struct Atom {
+    /// MathML of the element.
+    mathml: String,
+
+    /// Whether scripts are placed above and below the element in
+    /// display mode, rather than beside it.
+    limits: bool,
+
+    /// Whether the element is the name of a function.
+    function: bool,
+}
+
+impl Atom {
+    /// Construct a new atom without limits.
+    /// 
+    /// # Parameters
+    /// - `mathml` (`String`): the MathML of the element
+    /// 
+    /// # Returns
+    /// A new `Atom`.
+    fn new(mathml: String) -> Self {
+        Self {
+            mathml,
+            limits: false,
+            function: false,
+        }
+    }
+}
+
+/// A parser that converts a LaTeX equation into MathML.
+pub struct Latex {
+    /// Characters of the equation.
+    chars: Vec<char>,
+
+    /// Current index in the characters.
+    index: usize,
+
+    /// Whether the equation is in display mode.
+    display: bool,
+}
+
+impl Latex {
+    /// Construct a new parser.
+    /// 
+    /// # Parameters
+    /// - `tex` (`&str`): the LaTeX source of the equation
+    /// - `display` (`bool`): whether the equation is in display mode
+    /// 
+    /// # Returns
+    /// A new `Latex` parser.
+    pub fn new(tex: &str, display: bool) -> Self {
+        Self {
+            chars: tex.chars().collect(),
+            index: 0,
+            display,
+        }
+    }
+
+    /// Convert the equation into MathML.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// An `Option` containing the MathML of the equation, or `None` if
+    /// the equation uses unsupported LaTeX.
+    pub fn parse(&mut self) -> Option<String> {
+        let row = self.row()?;
+
+        // Anything left over, such as a stray `}`, is unsupported
+        if self.peek().is_some() {
+            return None;
+        }
+
+        Some (row.concat())
+    }
+
+    /// Skip any whitespace.
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.index).is_some_and(|c| c.is_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    /// Get the next token, advancing the parser.
+    fn next(&mut self) -> Option<Token> {
+        use Token::*;
+
+        self.skip_whitespace();
+
+        let c = *self.chars.get(self.index)?;
+        self.index += 1;
+
+        let token = match c {
+            '\\' => {
+                let mut name = String::new();
+
+                // Commands are either a run of letters or a single other character
+                while let Some (&l) = self.chars.get(self.index).filter(|l| l.is_ascii_alphabetic()) {
+                    name.push(l);
+                    self.index += 1;
+                }
+                if name.is_empty() {
+                    if let Some (&o) = self.chars.get(self.index) {
+                        name.push(o);
+                        self.index += 1;
+                    }
+                }
+
+                Command (name)
+            },
+            '{' => Open,
+            '}' => Close,
+            '^' => Sup,
+            '_' => Sub,
+            '&' => Amp,
+            c => Char (c),
+        };
+
+        Some (token)
+    }
+
+    /// Get the next token without advancing the parser.
+    fn peek(&mut self) -> Option<Token> {
+        let index = self.index;
+        let token = self.next();
+        self.index = index;
+        token
+    }
+
+    /// Check whether the next token is a given command.
+    fn peek_command(&mut self, name: &str) -> bool {
+        matches!(self.peek(), Some (Token::Command (c)) if c == name)
+    }
+
+    /// Consume a closing brace.
+    fn close(&mut self) -> Option<()> {
+        if self.next()? == Token::Close {
+            Some (())
+        } else {
+            None
+        }
+    }
+
+    /// Parse a row of elements, up to the end of the enclosing group.
+    fn row(&mut self) -> Option<Vec<String>> {
+        let mut row = Vec::new();
+
+        loop {
+            match self.peek() {
+                None | Some (Token::Close) | Some (Token::Amp) => break,
+                Some (Token::Command (c)) if c == "\\" || c == "right" || c == "end" => break,
+                _ => (),
+            }
+
+            let atom = self.atom()?;
+            let mathml = self.scripts(atom)?;
+
+            if !mathml.is_empty() {
+                row.push(mathml);
+            }
+        }
+
+        Some (row)
+    }
+
+    /// Parse a single element.
+    fn atom(&mut self) -> Option<Atom> {
+        // Scripts without a base are attached to an empty element
+        match self.peek()? {
+            Token::Sup | Token::Sub | Token::Char ('\'') => return Some (Atom::new(String::new())),
+            _ => (),
+        }
+
+        match self.next()? {
+            Token::Open => {
+                let row = self.row()?;
+                self.close()?;
+                Some (Atom::new(mrow(row)))
+            },
+            Token::Char (c) => self.character(c, true),
+            Token::Command (name) => self.command(&name),
+            _ => None,
+        }
+    }
+
+    /// Parse the argument of a command or a script, which is either a
+    /// group or a single element.
+    fn argument(&mut self) -> Option<String> {
+        match self.next()? {
+            Token::Open => {
+                let row = self.row()?;
+                self.close()?;
+                Some (mrow(row))
+            },
+            Token::Char (c) => Some (self.character(c, false)?.mathml),
+            Token::Command (name) => Some (self.command(&name)?.mathml),
+            _ => None,
+        }
+    }
+
+    /// Parse the argument of a command as plain text, without
+    /// converting it.
+    fn raw(&mut self) -> Option<String> {
+        self.skip_whitespace();
+
+        let c = *self.chars.get(self.index)?;
+        self.index += 1;
+
+        // Arguments may be a single character, as in `\mathbb R`
+        match c {
+            '{' => (),
+            '\\' | '}' => return None,
+            c => return Some (c.to_string()),
+        }
+
+        let mut raw = String::new();
+        let mut depth = 0;
+
+        loop {
+            let c = *self.chars.get(self.index)?;
+            self.index += 1;
+
+            match c {
+                '\\' => {
+                    raw.push(c);
+                    raw.push(*self.chars.get(self.index)?);
+                    self.index += 1;
+                },
+                '{' => {
+                    depth += 1;
+                    raw.push(c);
+                },
+                '}' if depth == 0 => return Some (raw),
+                '}' => {
+                    depth -= 1;
+                    raw.push(c);
+                },
+                c => raw.push(c),
+            }
+        }
+    }
+
+    /// Parse a delimiter after `\left`, `\right`, `\middle`, or `\big`.
+    fn delimiter(&mut self) -> Option<&'static str> {
+        match self.next()? {
+            Token::Char (c) => symbols::delimiter(&c.to_string()),
+            Token::Command (c) if c == "|" => Some ("‖"),
+            Token::Command (c) => symbols::delimiter(&c),
+            _ => None,
+        }
+    }
+
+    /// Attach any superscripts, subscripts, and primes to an element.
+    fn scripts(&mut self, atom: Atom) -> Option<String> {
+        let mut limits = atom.limits && self.display;
+        let mut sub = None;
+        let mut sup = None;
+        let mut primes = 0;
+
+        loop {
+            match self.peek() {
+                Some (Token::Command (c)) if atom.limits && c == "limits" => limits = true,
+                Some (Token::Command (c)) if atom.limits && c == "nolimits" => limits = false,
+                Some (Token::Sup) if sup.is_none() => {
+                    self.next();
+                    sup = Some (self.argument()?);
+                    continue;
+                },
+                Some (Token::Sub) if sub.is_none() => {
+                    self.next();
+                    sub = Some (self.argument()?);
+                    continue;
+                },
+                Some (Token::Char ('\'')) if sup.is_none() => primes += 1,
+                _ => break,
+            }
+
+            self.next();
+        }
+
+        // Double superscripts and subscripts are errors in LaTeX
+        if matches!(self.peek(), Some (Token::Sup) | Some (Token::Sub) | Some (Token::Char ('\''))) {
+            return None;
+        }
+
+        let sup = match (primes, sup) {
+            (0, s) => s,
+            (n, None) => Some (format!("<mo>{}</mo>", "′".repeat(n))),
+            (n, Some (s)) => Some (format!("<mrow><mo>{}</mo>{}</mrow>", "′".repeat(n), s)),
+        };
+
+        let base = if atom.mathml.is_empty() && (sub.is_some() || sup.is_some()) {
+            String::from("<mrow></mrow>")
+        } else {
+            atom.mathml
+        };
+
+        let (under, over, underover) = if limits {
+            ("munder", "mover", "munderover")
+        } else {
+            ("msub", "msup", "msubsup")
+        };
+
+        let mut mathml = match (sub, sup) {
+            (None, None) => base,
+            (Some (b), None) => format!("<{0}>{1}{2}</{0}>", under, base, b),
+            (None, Some (p)) => format!("<{0}>{1}{2}</{0}>", over, base, p),
+            (Some (b), Some (p)) => format!("<{0}>{1}{2}{3}</{0}>", underover, base, b, p),
+        };
+
+        // Function names are followed by an invisible function application
+        if atom.function {
+            mathml.push_str("<mo>&#x2061;</mo>");
+        }
+
+        Some (mathml)
+    }
+
+    /// Parse a character.
+    /// 
+    /// # Parameters
+    /// - `c` (`char`): the character
+    /// - `number` (`bool`): whether to continue a number with the
+    ///   digits that follow it
+    /// 
+    /// # Returns
+    /// An `Option` containing the element, if the character is
+    /// supported.
+    fn character(&mut self, c: char, number: bool) -> Option<Atom> {
+        let digit = |i: usize| self.chars.get(i).is_some_and(|d| d.is_ascii_digit());
+
+        if c.is_ascii_digit() || (c == '.' && digit(self.index)) {
+            let mut n = c.to_string();
+
+            if number {
+                while let Some (&d) = self.chars.get(self.index) {
+                    if !(d.is_ascii_digit() || (d == '.' && digit(self.index + 1))) {
+                        break;
+                    }
+                    n.push(d);
+                    self.index += 1;
+                }
+            }
+
+            return Some (Atom::new(format!("<mn>{}</mn>", n)));
+        }
+
+        let mathml = match c {
+            c if c.is_alphabetic() => format!("<mi>{}</mi>", c),
+            '-' => operator("−"),
+            '*' => operator("∗"),
+            '~' => String::from("<mspace width=\"0.25em\"/>"),
+            '+' | '=' | '<' | '>' | '(' | ')' | '[' | ']' | '|' | '/'
+                | ',' | ';' | ':' | '!' | '?' | '.' => operator(&c.to_string()),
+            c if !c.is_ascii() => operator(&c.to_string()),
+            _ => return None,
+        };
+
+        Some (Atom::new(mathml))
+    }
+
+    /// Parse a command.
+    /// 
+    /// # Parameters
+    /// - `name` (`&str`): the name of the command, without its backslash
+    /// 
+    /// # Returns
+    /// An `Option` containing the element, if the command is supported.
+    fn command(&mut self, name: &str) -> Option<Atom> {
+        if let Some ((kind, c)) = symbols::symbol(name) {
+            let c = c.to_string();
+            let mathml = match kind {
+                Kind::Identifier => format!("<mi>{}</mi>", c),
+                Kind::Upright => format!("<mi mathvariant=\"normal\">{}</mi>", c),
+                Kind::Operator => operator(&c),
+            };
+
+            return Some (Atom::new(mathml));
+        }
+
+        if let Some ((c, limits)) = symbols::large(name) {
+            return Some (Atom {
+                mathml: format!("<mo>{}</mo>", c),
+                limits,
+                function: false,
+            });
+        }
+
+        if let Some (limits) = symbols::function(name) {
+            let text = match name {
+                "limsup" => "lim sup",
+                "liminf" => "lim inf",
+                n => n,
+            };
+
+            return Some (Atom {
+                mathml: format!("<mi>{}</mi>", text),
+                limits,
+                function: true,
+            });
+        }
+
+        if let Some ((c, stretchy, under)) = symbols::accent(name) {
+            let base = self.argument()?;
+
+            let mathml = if under {
+                format!("<munder accentunder=\"true\">{}<mo stretchy=\"{}\">{}</mo></munder>", base, stretchy, c)
+            } else {
+                format!("<mover accent=\"true\">{}<mo stretchy=\"{}\">{}</mo></mover>", base, stretchy, c)
+            };
+
+            return Some (Atom::new(mathml));
+        }
+
+        if let Some (width) = symbols::space(name) {
+            return Some (Atom::new(format!("<mspace width=\"{}\"/>", width)));
+        }
+
+        if let Some (size) = symbols::size(name) {
+            let delimiter = self.delimiter()?;
+
+            return Some (Atom::new(format!("<mo minsize=\"{0}\" maxsize=\"{0}\">{1}</mo>", size, escape(delimiter))));
+        }
+
+        let mathml = match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument()?;
+                let denominator = self.argument()?;
+                let frac = format!("<mfrac>{}{}</mfrac>", numerator, denominator);
+
+                match name {
+                    "dfrac" => format!("<mstyle displaystyle=\"true\">{}</mstyle>", frac),
+                    "tfrac" => format!("<mstyle displaystyle=\"false\">{}</mstyle>", frac),
+                    _ => frac,
+                }
+            },
+            "binom" => {
+                let n = self.argument()?;
+                let k = self.argument()?;
+
+                format!("<mrow>{}<mfrac linethickness=\"0\">{}{}</mfrac>{}</mrow>", operator("("), n, k, operator(")"))
+            },
+            "sqrt" => {
+                // The index of a root is given in square brackets
+                self.skip_whitespace();
+                let index = if self.chars.get(self.index) == Some (&'[') {
+                    let start = self.index + 1;
+                    let end = start + self.chars[start..].iter().position(|&c| c == ']')?;
+                    self.index = end + 1;
+
+                    let tex: String = self.chars[start..end].iter().collect();
+                    Some (Latex::new(&tex, self.display).parse()?)
+                } else {
+                    None
+                };
+
+                let radicand = self.argument()?;
+
+                if let Some (i) = index {
+                    format!("<mroot>{}{}</mroot>", radicand, mrow(vec![i]))
+                } else {
+                    format!("<msqrt>{}</msqrt>", radicand)
+                }
+            },
+            "left" => {
+                let open = self.delimiter()?;
+                let row = self.row()?;
+                if !self.peek_command("right") {
+                    return None;
+                }
+                self.next();
+                let close = self.delimiter()?;
+
+                format!("<mrow>{}{}{}</mrow>", fence(open), row.concat(), fence(close))
+            },
+            "middle" => fence(self.delimiter()?),
+            "text" | "textrm" | "textnormal" | "textit" | "textbf" | "mbox" => {
+                let text = text(&self.raw()?)?;
+
+                match name {
+                    "textit" => format!("<mtext mathvariant=\"italic\">{}</mtext>", text),
+                    "textbf" => format!("<mtext mathvariant=\"bold\">{}</mtext>", text),
+                    _ => format!("<mtext>{}</mtext>", text),
+                }
+            },
+            "operatorname" => {
+                let text = word(&self.raw()?)?;
+
+                // Single letters are italic unless marked otherwise
+                let mathml = if text.chars().count() == 1 {
+                    format!("<mi mathvariant=\"normal\">{}</mi>", text)
+                } else {
+                    format!("<mi>{}</mi>", text)
+                };
+
+                return Some (Atom {
+                    mathml,
+                    limits: false,
+                    function: true,
+                });
+            },
+            "mathrm" => format!("<mi mathvariant=\"normal\">{}</mi>", word(&self.raw()?)?),
+            "mathit" => format!("<mi mathvariant=\"italic\">{}</mi>", word(&self.raw()?)?),
+            "mathbb" | "mathbf" | "mathcal" | "mathfrak" | "mathsf" | "mathtt" => {
+                let mut row = Vec::new();
+
+                for c in self.raw()?.chars().filter(|c| !c.is_whitespace()) {
+                    let letter = symbols::alphabet(name, c)?;
+
+                    row.push(if c.is_ascii_digit() {
+                        format!("<mn>{}</mn>", letter)
+                    } else {
+                        format!("<mi>{}</mi>", letter)
+                    });
+                }
+
+                mrow(row)
+            },
+            "overset" | "stackrel" | "underset" => {
+                let script = self.argument()?;
+                let base = self.argument()?;
+
+                if name == "underset" {
+                    format!("<munder>{}{}</munder>", base, script)
+                } else {
+                    format!("<mover>{}{}</mover>", base, script)
+                }
+            },
+            "pmod" => {
+                let n = self.argument()?;
+
+                format!(
+                    "<mrow><mspace width=\"0.4444em\"/>{}<mi>mod</mi><mspace width=\"0.3333em\"/>{}{}</mrow>",
+                    operator("("),
+                    n,
+                    operator(")"),
+                )
+            },
+            "bmod" => String::from("<mo lspace=\"0.2222em\" rspace=\"0.2222em\">mod</mo>"),
+            "begin" => self.environment()?,
+
+            // Display style is already set by the delimiters
+            "displaystyle" | "textstyle" => String::new(),
+            _ => return None,
+        };
+
+        Some (Atom::new(mathml))
+    }
+
+    /// Parse an environment, after its `\begin` command.
+    fn environment(&mut self) -> Option<String> {
+        let name = self.raw()?;
+
+        let (open, close, attributes) = match name.as_str() {
+            "matrix" => ("", "", ""),
+            "pmatrix" => ("(", ")", ""),
+            "bmatrix" => ("[", "]", ""),
+            "Bmatrix" => ("{", "}", ""),
+            "vmatrix" => ("|", "|", ""),
+            "Vmatrix" => ("‖", "‖", ""),
+            "cases" => ("{", "", " columnalign=\"left left\""),
+            "aligned" => ("", "", " columnalign=\"right left\" columnspacing=\"0\" displaystyle=\"true\""),
+            _ => return None,
+        };
+
+        let mut rows = Vec::new();
+
+        loop {
+            let mut cells = Vec::new();
+
+            loop {
+                cells.push(format!("<mtd>{}</mtd>", self.row()?.concat()));
+
+                if self.peek() != Some (Token::Amp) {
+                    break;
+                }
+                self.next();
+            }
+
+            rows.push(format!("<mtr>{}</mtr>", cells.concat()));
+
+            if !self.peek_command("\\") {
+                break;
+            }
+            self.next();
+
+            // A line break may end the last row
+            if self.peek_command("end") {
+                break;
+            }
+        }
+
+        if !self.peek_command("end") {
+            return None;
+        }
+        self.next();
+        if self.raw()? != name {
+            return None;
+        }
+
+        Some (format!("<mrow>{}<mtable{}>{}</mtable>{}</mrow>", fence(open), attributes, rows.concat(), fence(close)))
+    }
+}
+
+/// Construct an operator.
+/// 
+/// # Parameters
+/// - `c` (`&str`): the operator
+/// 
+/// # Returns
+/// A `String` containing the `<mo>` element.
+fn operator(c: &str) -> String {
+    if FENCES.contains(&c) {
+        format!("<mo stretchy=\"false\">{}</mo>", escape(c))
+    } else {
+        format!("<mo>{}</mo>", escape(c))
+    }
+}
+
+/// Construct a delimiter that stretches to the height of its contents.
+/// 
+/// # Parameters
+/// - `c` (`&str`): the delimiter, or an empty string for no delimiter
+/// 
+/// # Returns
+/// A `String` containing the `<mo>` element, if any.
+fn fence(c: &str) -> String {
+    if c.is_empty() {
+        String::new()
+    } else {
+        format!("<mo fence=\"true\" stretchy=\"true\">{}</mo>", escape(c))
+    }
+}
+
+/// Group a row of elements.
+/// 
+/// # Parameters
+/// - `row` (`Vec<String>`): the MathML of each element
+/// 
+/// # Returns
+/// A `String` containing the element, or an `<mrow>` element if there
+/// is more or less than one.
+fn mrow(row: Vec<String>) -> String {
+    if row.len() == 1 {
+        row.concat()
+    } else {
+        format!("<mrow>{}</mrow>", row.concat())
+    }
+}
+
+/// Convert the argument of a text command, such as `\text`.
+/// 
+/// # Parameters
+/// - `raw` (`&str`): the argument
+/// 
+/// # Returns
+/// An `Option` containing the escaped text, if it contains no
+/// commands other than escaped characters.
+fn text(raw: &str) -> Option<String> {
+    let mut text = String::new();
+    let mut chars = raw.chars();
+
+    while let Some (c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                e @ ('{' | '}' | '%' | '$' | '&' | '_' | '#' | ' ') => text.push(e),
+                _ => return None,
+            },
+            '{' | '}' => (),
+            '~' => text.push(' '),
+            c => text.push(c),
+        }
+    }
+
+    // Spaces at either end of the text are kept
+    let trimmed = text.trim_matches(' ');
+    let before = text.len() - text.trim_start_matches(' ').len();
+    let after = if trimmed.is_empty() {
+        0
+    } else {
+        text.len() - text.trim_end_matches(' ').len()
+    };
+
+    Some (format!("{}{}{}", "&#160;".repeat(before), escape(trimmed), "&#160;".repeat(after)))
+}
+
+/// Convert the argument of a command that takes a word, such as
+/// `\mathrm`.
+/// 
+/// # Parameters
+/// - `raw` (`&str`): the argument
+/// 
+/// # Returns
+/// An `Option` containing the word, if it contains only letters and
+/// digits.
+fn word(raw: &str) -> Option<String> {
+    let word: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if !word.is_empty() && word.chars().all(|c| c.is_alphanumeric()) {
+        Some (word)
+    } else {
+        None
+    }
+}
+
+/// Escape the characters of a string that are special in HTML.
+/// 
+/// # Parameters
+/// - `s` (`&str`): the string
+/// 
+/// # Returns
+/// A `String` containing the escaped string.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,99 @@
+//! Math renderer for the Blog Builder.
+
+// Enforce all warnings.
+#![deny(warnings)]
+
+// Enforce all documentation.
+#![deny(missing_docs)]
+
+mod latex;
+mod symbols;
+
+use latex::Latex;
+
+/// Render a LaTeX equation as MathML.
+/// 
+/// Only a common subset of LaTeX is supported.  Equations using
+/// anything else are not rendered, so that they may be displayed by
+/// MathJax instead.
+/// 
+/// # Parameters
+/// - `tex` (`&str`): the LaTeX source of the equation, without its
+///   delimiters
+/// - `display` (`bool`): whether the equation is displayed on its own
+///   line, rather than inline
+/// 
+/// # Returns
+/// An `Option` containing the `<math>` element, if the equation is
+/// supported.
+pub fn render(tex: &str, display: bool) -> Option<String> {
+    let body = Latex::new(tex, display).parse()?;
+
+    let math = if display {
+        format!("<math display=\"block\">{}</math>", body)
+    } else {
+        format!("<math>{}</math>", body)
+    };
+
+    Some (math)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    /// Render an inline equation, without its `<math>` element.
+    fn body(tex: &str) -> Option<String> {
+        render(tex, false).map(|m| m["<math>".len()..m.len() - "</math>".len()].to_owned())
+    }
+
+    #[test]
+    fn fractions_and_roots() {
+        assert_eq!(body("\\frac{a}{b}").unwrap(), "<mfrac><mi>a</mi><mi>b</mi></mfrac>");
+        assert_eq!(body("\\sqrt{2}").unwrap(), "<msqrt><mn>2</mn></msqrt>");
+        assert_eq!(body("\\sqrt[3]{x}").unwrap(), "<mroot><mi>x</mi><mn>3</mn></mroot>");
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(body("x_i^2").unwrap(), "<msubsup><mi>x</mi><mi>i</mi><mn>2</mn></msubsup>");
+        assert_eq!(body("\\alpha \\leq 2").unwrap(), "<mi>\u{3b1}</mi><mo>\u{2264}</mo><mn>2</mn>");
+    }
+
+    #[test]
+    fn fences_and_environments() {
+        let fence = |c: &str| format!("<mo fence=\"true\" stretchy=\"true\">{}</mo>", c);
+
+        assert_eq!(body("\\left( x \\right)").unwrap(), format!("<mrow>{}<mi>x</mi>{}</mrow>", fence("("), fence(")")));
+
+        assert_eq!(
+            body("\\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}").unwrap(),
+            format!(
+                "<mrow>{}<mtable><mtr><mtd><mi>a</mi></mtd><mtd><mi>b</mi></mtd></mtr><mtr><mtd><mi>c</mi></mtd><mtd><mi>d</mi></mtd></mtr></mtable>{}</mrow>",
+                fence("("),
+                fence(")"),
+            ),
+        );
+
+        let cases = body("\\begin{cases} 1 & x > 0 \\\\ 0 & \\text{otherwise} \\end{cases}").unwrap();
+        assert!(cases.starts_with(&format!("<mrow>{}<mtable columnalign=\"left left\">", fence("{"))), "{}", cases);
+        assert!(cases.contains("<mo>&gt;</mo>") && cases.contains("<mtext>otherwise</mtext>"), "{}", cases);
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        assert_eq!(body("\\text{a < b & c}").unwrap(), "<mtext>a &lt; b &amp; c</mtext>");
+    }
+
+    #[test]
+    fn display_equations_are_blocks() {
+        assert_eq!(render("x", true).unwrap(), "<math display=\"block\"><mi>x</mi></math>");
+    }
+
+    #[test]
+    fn unsupported_equations_are_not_rendered() {
+        for tex in ["x^2^3", "\\color{red}{x}", "{x", "x}", "\\frac{a}"] {
+            assert_eq!(render(tex, false), None, "{}", tex);
+        }
+    }
+}
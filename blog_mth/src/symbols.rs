@@ -0,0 +1,417 @@
+//! LaTeX symbols and their Unicode equivalents.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Kinds of symbols, by the MathML element that displays them.
+pub enum Kind {
+    /// Identifier (`mi`), such as a letter or a constant.
+    Identifier,
+
+    /// Upright identifier (`mi mathvariant="normal"`), such as an
+    /// uppercase Greek letter.
+    Upright,
+
+    /// Operator (`mo`), such as a relation or a binary operator.
+    Operator,
+}
+
+/// Look up a LaTeX command that displays a single symbol.
+/// 
+/// # Parameters
+/// - `name` (`&str`): the name of the command, without its backslash
+/// 
+/// # Returns
+/// An `Option` containing the kind of symbol and the symbol itself.
+pub fn symbol(name: &str) -> Option<(Kind, char)> {
+    use Kind::*;
+    let symbol = match name {
+        // Lowercase Greek letters
+        "alpha" => (Identifier, 'α'),
+        "beta" => (Identifier, 'β'),
+        "gamma" => (Identifier, 'γ'),
+        "delta" => (Identifier, 'δ'),
+        "epsilon" => (Identifier, 'ϵ'),
+        "varepsilon" => (Identifier, 'ε'),
+        "zeta" => (Identifier, 'ζ'),
+        "eta" => (Identifier, 'η'),
+        "theta" => (Identifier, 'θ'),
+        "vartheta" => (Identifier, 'ϑ'),
+        "iota" => (Identifier, 'ι'),
+        "kappa" => (Identifier, 'κ'),
+        "lambda" => (Identifier, 'λ'),
+        "mu" => (Identifier, 'μ'),
+        "nu" => (Identifier, 'ν'),
+        "xi" => (Identifier, 'ξ'),
+        "omicron" => (Identifier, 'ο'),
+        "pi" => (Identifier, 'π'),
+        "varpi" => (Identifier, 'ϖ'),
+        "rho" => (Identifier, 'ρ'),
+        "varrho" => (Identifier, 'ϱ'),
+        "sigma" => (Identifier, 'σ'),
+        "varsigma" => (Identifier, 'ς'),
+        "tau" => (Identifier, 'τ'),
+        "upsilon" => (Identifier, 'υ'),
+        "phi" => (Identifier, 'ϕ'),
+        "varphi" => (Identifier, 'φ'),
+        "chi" => (Identifier, 'χ'),
+        "psi" => (Identifier, 'ψ'),
+        "omega" => (Identifier, 'ω'),
+
+        // Uppercase Greek letters
+        "Gamma" => (Upright, 'Γ'),
+        "Delta" => (Upright, 'Δ'),
+        "Theta" => (Upright, 'Θ'),
+        "Lambda" => (Upright, 'Λ'),
+        "Xi" => (Upright, 'Ξ'),
+        "Pi" => (Upright, 'Π'),
+        "Sigma" => (Upright, 'Σ'),
+        "Upsilon" => (Upright, 'Υ'),
+        "Phi" => (Upright, 'Φ'),
+        "Psi" => (Upright, 'Ψ'),
+        "Omega" => (Upright, 'Ω'),
+
+        // Other identifiers
+        "infty" => (Upright, '∞'),
+        "partial" => (Identifier, '∂'),
+        "nabla" => (Upright, '∇'),
+        "emptyset" => (Upright, '∅'),
+        "varnothing" => (Upright, '∅'),
+        "hbar" => (Identifier, 'ℏ'),
+        "ell" => (Identifier, 'ℓ'),
+        "aleph" => (Upright, 'ℵ'),
+        "Re" => (Upright, 'ℜ'),
+        "Im" => (Upright, 'ℑ'),
+        "angle" => (Upright, '∠'),
+        "triangle" => (Upright, '△'),
+        "square" => (Upright, '□'),
+        "top" => (Upright, '⊤'),
+        "bot" => (Upright, '⊥'),
+
+        // Binary operators
+        "cdot" => (Operator, '⋅'),
+        "times" => (Operator, '×'),
+        "div" => (Operator, '÷'),
+        "pm" => (Operator, '±'),
+        "mp" => (Operator, '∓'),
+        "ast" => (Operator, '∗'),
+        "star" => (Operator, '⋆'),
+        "circ" => (Operator, '∘'),
+        "bullet" => (Operator, '∙'),
+        "oplus" => (Operator, '⊕'),
+        "ominus" => (Operator, '⊖'),
+        "otimes" => (Operator, '⊗'),
+        "cup" => (Operator, '∪'),
+        "cap" => (Operator, '∩'),
+        "setminus" => (Operator, '∖'),
+        "wedge" | "land" => (Operator, '∧'),
+        "vee" | "lor" => (Operator, '∨'),
+
+        // Relations
+        "leq" | "le" => (Operator, '≤'),
+        "geq" | "ge" => (Operator, '≥'),
+        "leqslant" => (Operator, '⩽'),
+        "geqslant" => (Operator, '⩾'),
+        "neq" | "ne" => (Operator, '≠'),
+        "ll" => (Operator, '≪'),
+        "gg" => (Operator, '≫'),
+        "approx" => (Operator, '≈'),
+        "equiv" => (Operator, '≡'),
+        "sim" => (Operator, '∼'),
+        "simeq" => (Operator, '≃'),
+        "cong" => (Operator, '≅'),
+        "propto" => (Operator, '∝'),
+        "prec" => (Operator, '≺'),
+        "succ" => (Operator, '≻'),
+        "preceq" => (Operator, '⪯'),
+        "succeq" => (Operator, '⪰'),
+        "in" => (Operator, '∈'),
+        "notin" => (Operator, '∉'),
+        "ni" => (Operator, '∋'),
+        "subset" => (Operator, '⊂'),
+        "subseteq" => (Operator, '⊆'),
+        "supset" => (Operator, '⊃'),
+        "supseteq" => (Operator, '⊇'),
+        "perp" => (Operator, '⊥'),
+        "parallel" => (Operator, '∥'),
+        "mid" => (Operator, '∣'),
+        "nmid" => (Operator, '∤'),
+        "vdash" => (Operator, '⊢'),
+        "models" => (Operator, '⊨'),
+
+        // Logic
+        "forall" => (Operator, '∀'),
+        "exists" => (Operator, '∃'),
+        "nexists" => (Operator, '∄'),
+        "neg" | "lnot" => (Operator, '¬'),
+        "therefore" => (Operator, '∴'),
+        "because" => (Operator, '∵'),
+
+        // Arrows
+        "to" | "rightarrow" => (Operator, '→'),
+        "gets" | "leftarrow" => (Operator, '←'),
+        "leftrightarrow" => (Operator, '↔'),
+        "Rightarrow" => (Operator, '⇒'),
+        "Leftarrow" => (Operator, '⇐'),
+        "Leftrightarrow" => (Operator, '⇔'),
+        "longrightarrow" => (Operator, '⟶'),
+        "longleftarrow" => (Operator, '⟵'),
+        "implies" => (Operator, '⟹'),
+        "impliedby" => (Operator, '⟸'),
+        "iff" => (Operator, '⟺'),
+        "mapsto" => (Operator, '↦'),
+        "uparrow" => (Operator, '↑'),
+        "downarrow" => (Operator, '↓'),
+
+        // Dots
+        "ldots" | "dots" => (Operator, '…'),
+        "cdots" => (Operator, '⋯'),
+        "vdots" => (Operator, '⋮'),
+        "ddots" => (Operator, '⋱'),
+
+        // Delimiters
+        "langle" => (Operator, '⟨'),
+        "rangle" => (Operator, '⟩'),
+        "lfloor" => (Operator, '⌊'),
+        "rfloor" => (Operator, '⌋'),
+        "lceil" => (Operator, '⌈'),
+        "rceil" => (Operator, '⌉'),
+        "vert" | "lvert" | "rvert" => (Operator, '|'),
+        "Vert" | "lVert" | "rVert" => (Operator, '‖'),
+        "backslash" => (Operator, '\\'),
+
+        // Escaped characters
+        "{" => (Operator, '{'),
+        "}" => (Operator, '}'),
+        "|" => (Operator, '‖'),
+        "%" => (Operator, '%'),
+        "#" => (Operator, '#'),
+        "&" => (Operator, '&'),
+        "$" => (Operator, '$'),
+        "_" => (Operator, '_'),
+
+        "colon" => (Operator, ':'),
+        "lbrace" => (Operator, '{'),
+        "rbrace" => (Operator, '}'),
+        "prime" => (Operator, '′'),
+        _ => return None,
+    };
+
+    Some (symbol)
+}
+
+/// Look up a large operator, such as a sum or an integral.
+/// 
+/// # Parameters
+/// - `name` (`&str`): the name of the command, without its backslash
+/// 
+/// # Returns
+/// An `Option` containing the operator and whether its limits are
+/// placed above and below it, rather than beside it.
+pub fn large(name: &str) -> Option<(char, bool)> {
+    let large = match name {
+        "sum" => ('∑', true),
+        "prod" => ('∏', true),
+        "coprod" => ('∐', true),
+        "bigcup" => ('⋃', true),
+        "bigcap" => ('⋂', true),
+        "bigoplus" => ('⨁', true),
+        "bigotimes" => ('⨂', true),
+        "bigvee" => ('⋁', true),
+        "bigwedge" => ('⋀', true),
+        "int" => ('∫', false),
+        "iint" => ('∬', false),
+        "iiint" => ('∭', false),
+        "oint" => ('∮', false),
+        _ => return None,
+    };
+
+    Some (large)
+}
+
+/// Look up a named function, such as `sin` or `lim`.
+/// 
+/// # Parameters
+/// - `name` (`&str`): the name of the command, without its backslash
+/// 
+/// # Returns
+/// An `Option` containing whether the limits of the function are
+/// placed below it, rather than beside it.
+pub fn function(name: &str) -> Option<bool> {
+    let limits = match name {
+        "sin" | "cos" | "tan" | "sec" | "csc" | "cot"
+            | "arcsin" | "arccos" | "arctan"
+            | "sinh" | "cosh" | "tanh" | "coth"
+            | "log" | "ln" | "lg" | "exp"
+            | "arg" | "deg" | "dim" | "hom" | "ker" => false,
+        "lim" | "limsup" | "liminf" | "max" | "min" | "sup" | "inf"
+            | "det" | "gcd" | "Pr" => true,
+        _ => return None,
+    };
+
+    Some (limits)
+}
+
+/// Look up an accent placed over or under its argument.
+/// 
+/// # Parameters
+/// - `name` (`&str`): the name of the command, without its backslash
+/// 
+/// # Returns
+/// An `Option` containing the accent, whether it stretches to the
+/// width of its argument, and whether it is placed under its argument.
+pub fn accent(name: &str) -> Option<(char, bool, bool)> {
+    let accent = match name {
+        "hat" => ('^', false, false),
+        "widehat" => ('^', true, false),
+        "bar" => ('¯', false, false),
+        "overline" => ('‾', true, false),
+        "underline" => ('_', true, true),
+        "vec" => ('→', false, false),
+        "overrightarrow" => ('→', true, false),
+        "tilde" => ('~', false, false),
+        "widetilde" => ('~', true, false),
+        "dot" => ('˙', false, false),
+        "ddot" => ('¨', false, false),
+        _ => return None,
+    };
+
+    Some (accent)
+}
+
+/// Look up a space.
+/// 
+/// # Parameters
+/// - `name` (`&str`): the name of the command, without its backslash
+/// 
+/// # Returns
+/// An `Option` containing the width of the space, in ems.
+pub fn space(name: &str) -> Option<&'static str> {
+    let space = match name {
+        "," | "thinspace" => "0.1667em",
+        ":" | ">" | "medspace" => "0.2222em",
+        ";" | "thickspace" => "0.2778em",
+        "!" | "negthinspace" => "-0.1667em",
+        " " => "0.25em",
+        "quad" => "1em",
+        "qquad" => "2em",
+        _ => return None,
+    };
+
+    Some (space)
+}
+
+/// Look up the size of a delimiter enlarged by `\big` and similar
+/// commands.
+/// 
+/// # Parameters
+/// - `name` (`&str`): the name of the command, without its backslash
+/// 
+/// # Returns
+/// An `Option` containing the height of the delimiter, in ems.
+pub fn size(name: &str) -> Option<&'static str> {
+    let size = match name {
+        "big" | "bigl" | "bigr" | "bigm" => "1.2em",
+        "Big" | "Bigl" | "Bigr" | "Bigm" => "1.623em",
+        "bigg" | "biggl" | "biggr" | "biggm" => "2.047em",
+        "Bigg" | "Biggl" | "Biggr" | "Biggm" => "2.470em",
+        _ => return None,
+    };
+
+    Some (size)
+}
+
+/// Look up a delimiter that may follow `\left`, `\right`, or `\big`.
+/// 
+/// # Parameters
+/// - `delimiter` (`&str`): the delimiter, either a character or the
+///   name of a command
+/// 
+/// # Returns
+/// An `Option` containing the delimiter, or an empty string for `.`
+/// (no delimiter).
+pub fn delimiter(delimiter: &str) -> Option<&'static str> {
+    let delimiter = match delimiter {
+        "(" => "(",
+        ")" => ")",
+        "[" => "[",
+        "]" => "]",
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        "|" | "vert" | "lvert" | "rvert" => "|",
+        "Vert" | "lVert" | "rVert" => "‖",
+        "/" => "/",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "." => "",
+        _ => return None,
+    };
+
+    Some (delimiter)
+}
+
+/// Convert a letter or digit to a mathematical alphabet.
+/// 
+/// # Parameters
+/// - `alphabet` (`&str`): the name of the alphabet command, such as
+///   `mathbb`
+/// - `c` (`char`): the letter or digit
+/// 
+/// # Returns
+/// An `Option` containing the converted character, if the alphabet
+/// has one.
+pub fn alphabet(alphabet: &str, c: char) -> Option<char> {
+    // Letters that were in Unicode before the mathematical alphabets
+    let exception = match (alphabet, c) {
+        ("mathbb", 'C') => Some ('ℂ'),
+        ("mathbb", 'H') => Some ('ℍ'),
+        ("mathbb", 'N') => Some ('ℕ'),
+        ("mathbb", 'P') => Some ('ℙ'),
+        ("mathbb", 'Q') => Some ('ℚ'),
+        ("mathbb", 'R') => Some ('ℝ'),
+        ("mathbb", 'Z') => Some ('ℤ'),
+        ("mathcal", 'B') => Some ('ℬ'),
+        ("mathcal", 'E') => Some ('ℰ'),
+        ("mathcal", 'F') => Some ('ℱ'),
+        ("mathcal", 'H') => Some ('ℋ'),
+        ("mathcal", 'I') => Some ('ℐ'),
+        ("mathcal", 'L') => Some ('ℒ'),
+        ("mathcal", 'M') => Some ('ℳ'),
+        ("mathcal", 'R') => Some ('ℛ'),
+        ("mathfrak", 'C') => Some ('ℭ'),
+        ("mathfrak", 'H') => Some ('ℌ'),
+        ("mathfrak", 'I') => Some ('ℑ'),
+        ("mathfrak", 'R') => Some ('ℜ'),
+        ("mathfrak", 'Z') => Some ('ℨ'),
+        _ => None,
+    };
+
+    if exception.is_some() {
+        return exception;
+    }
+
+    // Start of the uppercase letters, lowercase letters, and digits
+    let (upper, lower, digits) = match alphabet {
+        "mathbf" => (0x1D400, Some (0x1D41A), Some (0x1D7CE)),
+        "mathbb" => (0x1D538, Some (0x1D552), Some (0x1D7D8)),
+        "mathcal" => (0x1D49C, None, None),
+        "mathfrak" => (0x1D504, Some (0x1D51E), None),
+        "mathsf" => (0x1D5A0, Some (0x1D5BA), Some (0x1D7E2)),
+        "mathtt" => (0x1D670, Some (0x1D68A), Some (0x1D7F6)),
+        _ => return None,
+    };
+
+    let code = if c.is_ascii_uppercase() {
+        upper + (c as u32 - 'A' as u32)
+    } else if c.is_ascii_lowercase() {
+        lower? + (c as u32 - 'a' as u32)
+    } else if c.is_ascii_digit() {
+        digits? + (c as u32 - '0' as u32)
+    } else {
+        return None;
+    };
+
+    char::from_u32(code)
+}
@@ -0,0 +1,121 @@
+//! Header anchors and tables of contents for the Blog Builder.
+
+use std::collections::HashSet;
+
+use crate::{
+    Expression,
+    Node,
+};
+
+/// Assign an anchor ID to each header in a list of nodes, and fill in
+/// each table of contents.
+/// 
+/// Anchor IDs are made from the text of each header.  Headers with the
+/// same text are numbered in order, as `overview`, `overview-1`, and
+/// so on.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// 
+/// # Returns
+/// None.
+pub fn assign(nodes: &mut [Node]) {
+    // Anchor IDs already taken, starting with the labels of math blocks
+    let mut taken: HashSet<String> = HashSet::new();
+    for node in nodes.iter() {
+        node.expression.walk(&mut |expr| if let Expression::MathBlock { label: Some (l), .. } = expr {
+            taken.insert(l.to_owned());
+        });
+    }
+
+    // Level, text, and anchor ID of each header, in order
+    let mut headers = Vec::new();
+
+    for node in nodes.iter_mut() {
+        node.expression.walk_mut(&mut |expr| {
+            use Expression::*;
+            let (level, text, id) = match expr {
+                H1 { text, id, .. } => (1, text, id),
+                H2 { text, id, .. } => (2, text, id),
+                H3 { text, id, .. } => (3, text, id),
+                H4 { text, id, .. } => (4, text, id),
+                H5 { text, id, .. } => (5, text, id),
+                H6 { text, id, .. } => (6, text, id),
+                _ => return,
+            };
+
+            *id = unique(&slugify(text), &mut taken);
+            headers.push((level, text.to_owned(), id.to_owned()));
+        });
+    }
+
+    // Tables of contents count levels from the outermost header on the page
+    let top = headers.iter().map(|(l, _, _)| *l).min().unwrap_or(1);
+
+    for node in nodes.iter_mut() {
+        node.expression.walk_mut(&mut |expr| if let Expression::Toc { depth, entries } = expr {
+            *entries = headers.iter()
+                .filter(|(l, _, _)| *l < top + *depth)
+                .cloned()
+                .collect();
+        });
+    }
+}
+
+/// Convert header text into an anchor ID.
+/// 
+/// Letters are made lowercase, spaces and punctuation between words
+/// become hyphens, and all other characters are removed.
+/// 
+/// # Parameters
+/// - `text` (`&str`): the header text
+/// 
+/// # Returns
+/// A `String` containing the anchor ID.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+
+    for word in text.split(|c: char| c.is_whitespace() || c == '-' || c == '_') {
+        let word: String = word.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(|c| c.to_lowercase())
+            .collect();
+
+        if word.is_empty() {
+            continue;
+        }
+
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+
+    // Headers without any letters still need an anchor
+    if slug.is_empty() {
+        slug.push_str("section");
+    }
+
+    slug
+}
+
+/// Make an anchor ID unique by numbering it, if it is already taken.
+/// 
+/// # Parameters
+/// - `slug` (`&str`): the anchor ID
+/// - `taken` (`&mut HashSet<String>`): the anchor IDs already taken
+/// 
+/// # Returns
+/// A `String` containing the unique anchor ID, which is now taken.
+fn unique(slug: &str, taken: &mut HashSet<String>) -> String {
+    let mut id = slug.to_owned();
+    let mut n = 0;
+
+    while taken.contains(&id) {
+        n += 1;
+        id = format!("{}-{}", slug, n);
+    }
+
+    taken.insert(id.to_owned());
+    id
+}
@@ -0,0 +1,267 @@
+//! Control sequences defined outside of the Blog Builder.
+
+use crate::{
+    escape,
+    escape_uri,
+};
+
+/// Placeholder for the body of a block in the HTML of a control
+/// sequence.
+pub const BODY_PLACEHOLDER: &str = "{body}";
+
+/// Inline syntax defined by a crate extending the Blog Builder.
+/// 
+/// Inline syntax is text between an opening and a closing delimiter,
+/// on a single line, such as `{{Ctrl}}` or `:smile:`.  Delimiters
+/// should not contain characters that already have a meaning, such
+/// as `*`, `_`, or `[`.
+pub trait Inline {
+    /// Get the opening and closing delimiters of this syntax.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `(&str, &str)` containing the delimiters.
+    fn delimiters(&self) -> (&str, &str);
+
+    /// Emit HTML for this syntax.
+    /// 
+    /// # Parameters
+    /// - `text` (`&str`): the text between the delimiters, as written
+    /// 
+    /// # Returns
+    /// A `String` containing HTML.
+    fn emit(&self, text: &str) -> String;
+}
+
+/// A control sequence defined outside of the Blog Builder, either by
+/// the site or by a crate extending it.
+pub trait Control {
+    /// Get the number of arguments to this control sequence.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `usize` containing the number of arguments.
+    fn args(&self) -> usize;
+
+    /// Get the name of each argument to this control sequence.
+    /// 
+    /// Arguments may be given by name (`[name=value]`) as well as by
+    /// position.  By default, arguments are named by their positions,
+    /// starting with `1`.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `Vec<String>` containing the names.
+    fn names(&self) -> Vec<String> {
+        (1..=self.args()).map(|i| i.to_string()).collect()
+    }
+
+    /// Check if this control sequence is a block.
+    /// 
+    /// The body of a block is every line after the control sequence, up
+    /// to a line beginning with `::end`.  It is displayed in place of
+    /// `{body}` in the HTML, or after the HTML if there is no `{body}`.
+    /// By default, control sequences are not blocks.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `bool` indicating if this control sequence is a block.
+    fn block(&self) -> bool {
+        false
+    }
+
+    /// Emit HTML for this control sequence.
+    /// 
+    /// The number of arguments has already been checked.
+    /// 
+    /// # Parameters
+    /// - `values` (`&[String]`): the arguments, as written
+    /// 
+    /// # Returns
+    /// A `String` containing HTML.
+    fn emit(&self, values: &[String]) -> String;
+}
+
+#[derive(Clone, Debug)]
+/// A control sequence defined by the site, rather than by the
+/// Blog Builder.
+/// 
+/// Its HTML contains placeholders for its arguments, either by
+/// position (`{1}`, `{2}`, ...) or by name (`{id}`).
+pub struct CustomControl {
+    /// Name of each argument, if named.
+    names: Vec<Option<String>>,
+
+    /// HTML containing placeholders.
+    html: String,
+
+    /// Does this control sequence have a body?
+    block: bool,
+}
+
+impl CustomControl {
+    /// Construct a control sequence with positional arguments.
+    /// 
+    /// # Parameters
+    /// - `args` (`usize`): the number of arguments
+    /// - `html` (`&str`): the HTML, containing placeholders
+    /// 
+    /// # Returns
+    /// A new `CustomControl`.
+    pub fn new(args: usize, html: &str) -> Self {
+        Self {
+            names: vec![None; args],
+            html: html.to_owned(),
+            block: false,
+        }
+    }
+
+    /// Construct a control sequence with named arguments.
+    /// 
+    /// Named arguments may also be used by position.
+    /// 
+    /// # Parameters
+    /// - `names` (`&[String]`): the name of each argument
+    /// - `html` (`&str`): the HTML, containing placeholders
+    /// 
+    /// # Returns
+    /// A new `CustomControl`.
+    pub fn named(names: &[String], html: &str) -> Self {
+        Self {
+            names: names.iter().map(|n| Some (n.to_owned())).collect(),
+            html: html.to_owned(),
+            block: false,
+        }
+    }
+
+    /// Make this control sequence a block, whose body is displayed in
+    /// place of `{body}`.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// This `CustomControl`, as a block.
+    pub fn with_body(mut self) -> Self {
+        self.block = true;
+        self
+    }
+}
+
+impl Control for CustomControl {
+    fn args(&self) -> usize {
+        self.names.len()
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.names.iter()
+            .enumerate()
+            .map(|(i, n)| n.to_owned().unwrap_or_else(|| (i + 1).to_string()))
+            .collect()
+    }
+
+    fn block(&self) -> bool {
+        self.block
+    }
+
+    /// Fill in the placeholders of this control sequence.
+    /// 
+    /// Arguments are escaped, and placeholders that do not name an
+    /// argument are displayed as written.  Arguments inside of a tag
+    /// are treated as URIs, so that they cannot run a script.  In a
+    /// block, `{body}` is kept for the body, and braces in arguments
+    /// are escaped so that they cannot be mistaken for it.
+    /// 
+    /// # Parameters
+    /// - `values` (`&[String]`): the arguments
+    /// 
+    /// # Returns
+    /// A `String` containing HTML.
+    fn emit(&self, values: &[String]) -> String {
+        let mut output = String::new();
+        let mut rest = self.html.as_str();
+
+        while let Some (start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            // Find the argument named by this placeholder
+            let value = rest.find('}').and_then(|end| {
+                let name = rest[1..end].trim();
+
+                let index = if self.block && &rest[..=end] == BODY_PLACEHOLDER {
+                    None
+                } else if let Ok (i) = name.parse::<usize>() {
+                    // Positions start at one
+                    i.checked_sub(1)
+                } else {
+                    self.names.iter().position(|n| n.as_deref() == Some (name))
+                };
+
+                index.and_then(|i| values.get(i)).map(|v| (v, end))
+            });
+
+            if let Some ((v, end)) = value {
+                // Is this placeholder inside of a tag, such as in an attribute?
+                let tag = output.rfind('<') > output.rfind('>');
+
+                let v = if tag {
+                    escape_uri(v)
+                } else {
+                    escape(v)
+                };
+
+                if self.block {
+                    output.push_str(&v.replace('{', "&#123;"));
+                } else {
+                    output.push_str(&v);
+                }
+
+                rest = &rest[end + 1..];
+            } else {
+                output.push('{');
+                rest = &rest[1..];
+            }
+        }
+
+        output.push_str(rest);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blog_tkn::Tokenizer;
+
+    use crate::Parser;
+
+    use super::CustomControl;
+
+    #[test]
+    fn script_uri_is_replaced_in_attribute() {
+        let mut parser = Parser::new();
+        parser.define("button", CustomControl::named(&["text".to_string(), "href".to_string()], "<a href=\"{href}\">{text}</a>"));
+
+        let nodes = parser.parse(&mut Tokenizer::from("::button[text=Go][href=javascript:alert(1)]\n".to_owned()));
+
+        assert_eq!(nodes[0].expression.html(true), "<a href=\"#\">Go</a>");
+    }
+
+    #[test]
+    fn block_body_replaces_placeholder() {
+        let mut parser = Parser::new();
+        parser.define("aside", CustomControl::new(1, "<aside title=\"{1}\">{body}</aside>").with_body());
+
+        let nodes = parser.parse(&mut Tokenizer::from("::aside[{body}]\nHello\n::end\n".to_owned()));
+
+        assert_eq!(nodes[0].expression.html(true), "<aside title=\"&#123;body}\">\n\n<p>Hello</p></aside>");
+    }
+}
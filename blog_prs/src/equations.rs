@@ -0,0 +1,106 @@
+//! Equations rendered at build time for the Blog Builder.
+
+use crate::{
+    Expression,
+    Node,
+    inline::join,
+};
+
+/// Render the equations in a list of nodes.
+/// 
+/// Equations delimited by `\( ... \)` or `\[ ... \]` are replaced by
+/// the output of the renderer.  Equations that the renderer does not
+/// support are left as text, to be displayed by MathJax instead.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// - `render` (`&impl Fn(&str, bool) -> Option<String>`): a function
+///   that converts the LaTeX source of an equation, and whether it is
+///   displayed on its own line, into MathML
+/// 
+/// # Returns
+/// None.
+pub fn render(nodes: &mut [Node], render: &impl Fn(&str, bool) -> Option<String>) {
+    for node in nodes {
+        render_expr(&mut node.expression, render);
+    }
+}
+
+/// Render the equations in an expression.
+/// 
+/// # Parameters
+/// - `expression` (`&mut Expression`): the expression
+/// - `render` (`&impl Fn(&str, bool) -> Option<String>`): the renderer
+/// 
+/// # Returns
+/// None.
+fn render_expr(expression: &mut Expression, render: &impl Fn(&str, bool) -> Option<String>) {
+    for list in expression.lists_mut() {
+        for expr in join(list) {
+            match expr {
+                Expression::Text (t) => split(&t, render, list),
+                mut e => {
+                    render_expr(&mut e, render);
+                    list.push(e);
+                },
+            }
+        }
+    }
+}
+
+/// Split text around the equations in it.
+/// 
+/// # Parameters
+/// - `text` (`&str`): the text
+/// - `render` (`&impl Fn(&str, bool) -> Option<String>`): the renderer
+/// - `output` (`&mut Vec<Expression>`): the list of expressions to
+///   which the pieces of the text are added
+/// 
+/// # Returns
+/// None.
+fn split(text: &str, render: &impl Fn(&str, bool) -> Option<String>, output: &mut Vec<Expression>) {
+    // Text not yet added to the output
+    let mut pending = String::new();
+    let mut rest = text;
+
+    loop {
+        // Find the first opening delimiter
+        let (start, display) = match (rest.find("\\("), rest.find("\\[")) {
+            (Some (i), Some (d)) if d < i => (d, true),
+            (Some (i), _) => (i, false),
+            (None, Some (d)) => (d, true),
+            (None, None) => break,
+        };
+
+        let close = if display { "\\]" } else { "\\)" };
+        let end = if let Some (e) = rest[start + 2..].find(close) {
+            start + 2 + e
+        } else {
+            break;
+        };
+
+        let tex = &rest[start + 2..end];
+
+        if let Some (mathml) = render(tex.trim(), display) {
+            pending.push_str(&rest[..start]);
+            if !pending.is_empty() {
+                output.push(Expression::Text (std::mem::take(&mut pending)));
+            }
+
+            output.push(Expression::Math {
+                tex: tex.to_owned(),
+                display,
+                mathml,
+            });
+        } else {
+            pending.push_str(&rest[..end + 2]);
+        }
+
+        rest = &rest[end + 2..];
+    }
+
+    pending.push_str(rest);
+    if !pending.is_empty() {
+        output.push(Expression::Text (pending));
+    }
+}
@@ -25,6 +25,9 @@ pub enum ParseError {
     /// Mismatched delimiters.
     MismatchedDelimiters,
 
+    /// Emphasized text without a closing delimiter.
+    UnclosedEmphasis (String),
+
     /// Unrecognized control sequence.
     UnrecognizedControl (String),
 
@@ -40,8 +43,177 @@ pub enum ParseError {
         control: String,
     },
 
+    /// Required argument to control sequence not given.
+    MissingArgument {
+        /// Control sequence.
+        control: String,
+
+        /// Name of the argument.
+        argument: String,
+    },
+
+    /// Named argument that the control sequence does not accept.
+    UnknownArgument {
+        /// Control sequence.
+        control: String,
+
+        /// Name of the argument.
+        argument: String,
+
+        /// Names of the arguments that the control sequence accepts.
+        available: Vec<String>,
+    },
+
+    /// Argument to control sequence given more than once.
+    DuplicateArgument {
+        /// Control sequence.
+        control: String,
+
+        /// Name of the argument.
+        argument: String,
+    },
+
+    /// Argument to control sequence with an invalid value.
+    InvalidArgument {
+        /// Control sequence.
+        control: String,
+
+        /// Name of the argument.
+        argument: String,
+
+        /// Value of the argument.
+        value: String,
+
+        /// Description of the values allowed.
+        expected: String,
+    },
+
     /// No parselet available for token class.
     NoParselet (TokenClass),
+
+    /// Raw HTML block on a site that does not allow raw HTML.
+    RawHtmlDisabled,
+
+    /// Reference to a footnote that is never defined.
+    UndefinedFootnote (String),
+
+    /// Footnote defined more than once.
+    DuplicateFootnote (String),
+
+    /// Cross-reference to a label that is never defined.
+    UndefinedLabel (String),
+
+    /// Label used by more than one block.
+    DuplicateLabel (String),
+
+    /// Reference-style link to a reference that is never defined.
+    UndefinedLink (String),
+
+    /// Link reference definition with text after its URI that is not a
+    /// quoted title.
+    InvalidLinkDefinition (String),
+
+    /// Block control sequence without a closing `::end`.
+    UnclosedBlock (String),
+
+    /// End of a block without a matching control sequence to open it.
+    UnopenedBlock (String),
+
+    /// QED symbol outside of a proof.
+    QedOutsideProof,
+
+    /// Equation delimiter that is never closed.
+    UnclosedEquation (String),
+
+    /// Equation delimiter that closes an equation that was never opened.
+    UnopenedEquation (String),
+
+    /// Equation closed with a different delimiter than the one that
+    /// opened it.
+    MismatchedEquation {
+        /// Delimiter that opened the equation.
+        open: String,
+
+        /// Delimiter that closed the equation.
+        close: String,
+    },
+
+    /// Included file that could not be read.
+    IncludeNotFound (String),
+
+    /// Included file outside of the source directory.
+    IncludeOutsideSource (String),
+
+    /// File that includes itself, directly or indirectly.
+    CircularInclude (String),
+
+    /// Includes nested deeper than the given limit.
+    IncludeTooDeep (usize),
+}
+
+impl ParseError {
+    /// Get a suggestion for fixing this error.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// An `Option<String>` containing the hint, if available.
+    pub fn hint(&self) -> Option<String> {
+        use ParseError::*;
+        let hint = match self {
+            UnexpectedEof => "check for an unclosed bracket or parenthesis".to_string(),
+            UnrecognizedEmphasis => "use `*` for italics, `**` for bold, or `***` for both".to_string(),
+            TooManyHashes => "headers may have at most six hashes".to_string(),
+            MismatchedDelimiters => "emphasized text must be closed with the same delimiter that opened it".to_string(),
+            UnclosedEmphasis (d) => format!("close the emphasized text with `{}` on the same line", d),
+            UnrecognizedControl (_) => "see SYNTAX.md for the available control sequences, or define this one in `blog.toml`".to_string(),
+            IncorrectArgumentCount {
+                control: c,
+                ..
+            } => format!("each argument to control sequence '{}' must be enclosed in square brackets", c),
+            MissingArgument {
+                control: c,
+                argument: a,
+            } => format!("add the argument to control sequence '{}' in square brackets, as `[{}=...]` or by position", c, a),
+            UnknownArgument {
+                available,
+                ..
+            } => if available.is_empty() {
+                "this control sequence takes no arguments".to_string()
+            } else {
+                format!("the available arguments are {}", available.iter().map(|a| format!("`{}`", a)).collect::<Vec<String>>().join(", "))
+            },
+            DuplicateArgument { .. } => "each argument may only be given once".to_string(),
+            InvalidArgument {
+                expected,
+                ..
+            } => expected.to_owned(),
+            RawHtmlDisabled => "raw HTML is disabled by `raw_html = false` in `blog.toml`".to_string(),
+            UndefinedFootnote (l) => format!("define the footnote on its own line with `[^{}]: text`", l),
+            DuplicateFootnote (_) => "each footnote label may only be defined once".to_string(),
+            UndefinedLabel (_) => "label a math block with `[label=...]`, on this page or another page of the site".to_string(),
+            DuplicateLabel (_) => "each label may only be used once".to_string(),
+            UndefinedLink (l) => format!("define the link on its own line with `[{}]: URI`", l),
+            InvalidLinkDefinition (_) => "a link title must be enclosed in double quotes, single quotes, or parentheses".to_string(),
+            UnclosedBlock (_) => "close the block with `::end` on a line of its own".to_string(),
+            UnopenedBlock (_) => "open the block with `::mathblock[Type][Title]`, or remove this line".to_string(),
+            QedOutsideProof => "place `::qed` at the end of a `::mathblock[Proof][...]` block".to_string(),
+            UnclosedEquation (d) => format!("close the equation with `{}`; until then, emphasis is not recognized", closing(d)),
+            UnopenedEquation (d) => format!("open the equation with `{}`, or remove this delimiter", opening(d)),
+            MismatchedEquation {
+                open,
+                ..
+            } => format!("close the equation with `{}`", closing(open)),
+            IncludeNotFound (_) => "included paths are relative to the source directory".to_string(),
+            IncludeOutsideSource (_) => "only files inside of the source directory may be included".to_string(),
+            CircularInclude (_) => "remove one of the `include` control sequences in the cycle".to_string(),
+            IncludeTooDeep (d) => format!("includes may be nested at most {} levels deep", d),
+            ExpectedToken (_) | NoParselet (_) => return None,
+        };
+
+        Some (hint)
+    }
 }
 
 impl Display for ParseError {
@@ -54,14 +226,76 @@ impl Display for ParseError {
             UnrecognizedEmphasis => "unrecognized emphasis sequence",
             UnrecognizedControl (c) => &format!("unrecognized control sequence '{}'", c),
             MismatchedDelimiters => "mismatched delimiters",
+            UnclosedEmphasis (d) => &format!("unclosed emphasis delimiter '{}'", d),
             IncorrectArgumentCount {
                 expected: e,
                 actual: a,
                 control: c,
             } => &format!("expected {} argument(s) to control sequence '{}', got {} argument(s) instead", e, c, a),
+            MissingArgument {
+                control: c,
+                argument: a,
+            } => &format!("missing argument '{}' to control sequence '{}'", a, c),
+            UnknownArgument {
+                control: c,
+                argument: a,
+                ..
+            } => &format!("unknown argument '{}' to control sequence '{}'", a, c),
+            DuplicateArgument {
+                control: c,
+                argument: a,
+            } => &format!("argument '{}' to control sequence '{}' is given more than once", a, c),
+            InvalidArgument {
+                control: c,
+                argument: a,
+                value: v,
+                ..
+            } => &format!("invalid value '{}' for argument '{}' to control sequence '{}'", v, a, c),
             NoParselet (c) => &format!("could not handle token of class '{}'", c.display()),
+            RawHtmlDisabled => "raw HTML is not allowed on this site",
+            UndefinedFootnote (l) => &format!("undefined footnote '{}'", l),
+            DuplicateFootnote (l) => &format!("footnote '{}' is defined more than once", l),
+            UndefinedLabel (l) => &format!("undefined label '{}'", l),
+            DuplicateLabel (l) => &format!("label '{}' is used more than once", l),
+            UndefinedLink (l) => &format!("undefined link reference '{}'", l),
+            InvalidLinkDefinition (l) => &format!("invalid definition of link reference '{}'", l),
+            UnclosedBlock (c) => &format!("unclosed block '{}'", c),
+            UnopenedBlock (c) => &format!("'::{}' without a matching block", c),
+            QedOutsideProof => "'::qed' outside of a proof",
+            UnclosedEquation (d) => &format!("unclosed equation delimiter '{}'", d),
+            UnopenedEquation (d) => &format!("equation delimiter '{}' without a matching '{}'", d, opening(d)),
+            MismatchedEquation {
+                open,
+                close,
+            } => &format!("equation opened with '{}' is closed with '{}'", open, close),
+            IncludeNotFound (p) => &format!("could not read included file '{}'", p),
+            IncludeOutsideSource (p) => &format!("included file '{}' is outside of the source directory", p),
+            CircularInclude (p) => &format!("file '{}' includes itself", p),
+            IncludeTooDeep (_) => "includes are nested too deeply",
         };
 
         write!(f, "{}", output)
     }
+}
+
+/// Get the delimiter that closes an equation.
+/// 
+/// # Parameters
+/// - `open` (`&str`): the opening delimiter, `\[` or `\(`
+/// 
+/// # Returns
+/// A `&str` containing the closing delimiter.
+fn closing(open: &str) -> &str {
+    if open == "\\(" { "\\)" } else { "\\]" }
+}
+
+/// Get the delimiter that opens an equation.
+/// 
+/// # Parameters
+/// - `close` (`&str`): the closing delimiter, `\]` or `\)`
+/// 
+/// # Returns
+/// A `&str` containing the opening delimiter.
+fn opening(close: &str) -> &str {
+    if close == "\\)" { "\\(" } else { "\\[" }
 }
\ No newline at end of file
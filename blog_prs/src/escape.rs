@@ -0,0 +1,89 @@
+//! HTML escaping for the Blog Builder.
+
+/// Escape a string for use in HTML.
+/// 
+/// The output is safe to use both as element content and as the
+/// value of a quoted attribute.
+/// 
+/// # Parameters
+/// - `string` (`&str`): the raw string
+/// 
+/// # Returns
+/// A `String` with all HTML special characters escaped.
+pub fn escape(string: &str) -> String {
+    let mut output = String::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Escape a URI for use as the value of a quoted attribute.
+/// 
+/// URIs with a scheme that would run a script when followed are
+/// replaced with an empty fragment.
+/// 
+/// # Parameters
+/// - `uri` (`&str`): the raw URI
+/// 
+/// # Returns
+/// A `String` containing the escaped URI.
+pub fn escape_uri(uri: &str) -> String {
+    escape(safe_uri(uri))
+}
+
+/// Replace a URI that would run a script when followed.
+/// 
+/// # Parameters
+/// - `uri` (`&str`): the raw URI
+/// 
+/// # Returns
+/// A `&str` containing the URI, or an empty fragment.
+pub fn safe_uri(uri: &str) -> &str {
+    // Browsers ignore whitespace and control characters in the scheme
+    let cleaned = uri.chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_lowercase();
+
+    match cleaned.split_once(':') {
+        Some (("javascript", _)) | Some (("vbscript", _)) => "#",
+        _ => uri,
+    }
+}
+
+/// Escape a string for use inside of a single-quoted JavaScript
+/// or CSS string, within a quoted attribute.
+/// 
+/// # Parameters
+/// - `string` (`&str`): the raw string
+/// 
+/// # Returns
+/// A `String` with all special characters escaped.
+pub fn escape_string(string: &str) -> String {
+    let mut output = String::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '\\' | '\'' | '"' => {
+                output.push('\\');
+                output.push(c);
+            },
+
+            // Strings may not span lines
+            '\n' | '\r' => (),
+            _ => output.push(c),
+        }
+    }
+
+    escape(&output)
+}
@@ -5,28 +5,124 @@ use std::fmt::{
     self,
 };
 
-use crate::ParseError;
+use blog_tkn::Span;
+
+use crate::{
+    BODY_PLACEHOLDER,
+    ParseError,
+    escape::{
+        escape,
+        escape_string,
+        escape_uri,
+        safe_uri,
+    },
+};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+/// Alignment of a table column.
+pub enum Alignment {
+    /// Left-aligned.
+    Left,
+
+    /// Centered.
+    Center,
+
+    /// Right-aligned.
+    Right,
+}
+
+impl Alignment {
+    /// Get the CSS value of this alignment.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `&str` for use with the `text-align` property.
+    pub fn css(&self) -> &str {
+        use Alignment::*;
+        match self {
+            Left => "left",
+            Center => "center",
+            Right => "right",
+        }
+    }
+}
 
 #[derive(PartialEq, Clone, Debug)]
 /// Expressions available to the Blog Builder.
 pub enum Expression {
     /// H1.
-    H1 (String),
+    H1 {
+        /// Header text.
+        text: String,
+
+        /// Anchor ID, or empty if not yet assigned.
+        id: String,
+
+        /// Display a permalink to this header.
+        permalink: bool,
+    },
 
     /// H2.
-    H2 (String),
+    H2 {
+        /// Header text.
+        text: String,
+
+        /// Anchor ID, or empty if not yet assigned.
+        id: String,
+
+        /// Display a permalink to this header.
+        permalink: bool,
+    },
 
     /// H3.
-    H3 (String),
+    H3 {
+        /// Header text.
+        text: String,
+
+        /// Anchor ID, or empty if not yet assigned.
+        id: String,
+
+        /// Display a permalink to this header.
+        permalink: bool,
+    },
 
     /// H4.
-    H4 (String),
+    H4 {
+        /// Header text.
+        text: String,
+
+        /// Anchor ID, or empty if not yet assigned.
+        id: String,
+
+        /// Display a permalink to this header.
+        permalink: bool,
+    },
 
     /// H5.
-    H5 (String),
+    H5 {
+        /// Header text.
+        text: String,
+
+        /// Anchor ID, or empty if not yet assigned.
+        id: String,
+
+        /// Display a permalink to this header.
+        permalink: bool,
+    },
     
     /// H6.
-    H6 (String),
+    H6 {
+        /// Header text.
+        text: String,
+
+        /// Anchor ID, or empty if not yet assigned.
+        id: String,
+
+        /// Display a permalink to this header.
+        permalink: bool,
+    },
 
     /// Byline.
     Byline {
@@ -40,27 +136,162 @@ pub enum Expression {
     /// Paragraph (p).
     Paragraph (Vec<Expression>),
 
+    /// Unordered list (ul).
+    UnorderedList (Vec<Expression>),
+
+    /// Ordered list (ol).
+    OrderedList {
+        /// Number of the first item.
+        start: usize,
+
+        /// List items.
+        items: Vec<Expression>,
+    },
+
+    /// List item (li).
+    ListItem (Vec<Expression>),
+
+    /// Blockquote, containing block expressions (blockquote).
+    Blockquote (Vec<Expression>),
+
+    /// Table (table).
+    Table {
+        /// Alignment of each column, if provided.
+        alignments: Vec<Option<Alignment>>,
+
+        /// Cells of the header row.
+        header: Vec<Expression>,
+
+        /// Cells of each body row.
+        rows: Vec<Vec<Expression>>,
+    },
+
     /// Bold text (strong).
-    Bold (String),
+    Bold (Vec<Expression>),
 
     /// Italicized text (em).
-    Italics (String),
+    Italics (Vec<Expression>),
 
     /// Bold & italicized text (strong + em).
-    BoldItalics (String),
+    BoldItalics (Vec<Expression>),
 
     /// Raw text.
     Text (String),
 
+    /// Inline code (code).
+    Code (String),
+
     /// Hyperreference (a).
     Href {
         /// Human-readable text.
-        text: String,
+        text: Vec<Expression>,
 
         /// URI of reference.
         href: String,
     },
 
+    /// Reference-style hyperreference (a).
+    LinkRef {
+        /// Human-readable text.
+        text: Vec<Expression>,
+
+        /// Label of the link reference definition.
+        label: String,
+
+        /// URI of reference, or empty if not yet resolved.
+        href: String,
+
+        /// Title of the link, or empty if it has none.
+        title: String,
+
+        /// Location of the link, for error messages.
+        span: Span,
+    },
+
+    /// Link reference definition.
+    /// 
+    /// Definitions are not displayed; they supply the URI of each
+    /// reference-style hyperreference with the same label.
+    LinkDef {
+        /// Label of the definition.
+        label: String,
+
+        /// URI of reference.
+        href: String,
+
+        /// Title of the link, or empty if it has none.
+        title: String,
+    },
+
+    /// Footnote reference (sup.footnote-ref).
+    FootnoteRef {
+        /// Label of the footnote.
+        label: String,
+
+        /// Number of the footnote, in order of first reference.
+        number: usize,
+
+        /// Is this the first reference to the footnote?
+        first: bool,
+
+        /// Location of the reference, for error messages.
+        span: Span,
+    },
+
+    /// Cross-reference to a labelled block (a.ref).
+    Ref {
+        /// Label of the block.
+        label: String,
+
+        /// Text of the link, or empty if not yet resolved.
+        text: String,
+
+        /// URI of the block, or empty if not yet resolved.
+        href: String,
+
+        /// Location of the label, for error messages.
+        span: Span,
+    },
+
+    /// Equation rendered at build time (math).
+    Math {
+        /// LaTeX source of the equation.
+        tex: String,
+
+        /// Is this equation displayed on its own line?
+        display: bool,
+
+        /// MathML of the equation.
+        mathml: String,
+    },
+
+    /// Footnote definition.
+    /// 
+    /// Definitions are collected by the emitter and displayed at the
+    /// end of the page.
+    Footnote {
+        /// Label of the footnote.
+        label: String,
+
+        /// Number of the footnote, or zero if it is never referenced.
+        number: usize,
+
+        /// Text of the footnote.
+        text: Vec<Expression>,
+
+        /// Location of the label, for error messages.
+        span: Span,
+    },
+
+    /// Sidenote, displayed in the margin next to its reference (span.sidenote).
+    Sidenote {
+        /// Number of the note.
+        number: usize,
+
+        /// Text of the note.
+        text: Vec<Expression>,
+    },
+
     /// Floating image (img.float).
     FloatImage {
         /// Alternate text.
@@ -68,6 +299,12 @@ pub enum Expression {
 
         /// URI of image.
         href: String,
+
+        /// CSS width of image, if provided.
+        width: Option<String>,
+
+        /// Caption, if provided.
+        caption: Option<String>,
     },
 
     /// Full-width image (img.full).
@@ -77,6 +314,12 @@ pub enum Expression {
 
         /// URI of image.
         href: String,
+
+        /// CSS width of image, if provided.
+        width: Option<String>,
+
+        /// Caption, if provided.
+        caption: Option<String>,
     },
 
     /// Tile hyperlink (div.tile).
@@ -92,25 +335,86 @@ pub enum Expression {
 
         /// URI of image.
         image: String,
+
+        /// Browsing context in which to open the link, if provided.
+        target: Option<String>,
     },
 
-    /// Math block.
+    /// Math block (div.mathblock).
     MathBlock {
         /// Type of block (definition, theorem, lemma, ...).
         blocktype: String,
 
         /// Title of block.
         title: String,
+
+        /// Label for cross-references, if provided.
+        label: Option<String>,
+
+        /// Number of block, or empty if unnumbered.
+        number: String,
+
+        /// Contents of block.
+        body: Vec<Expression>,
     },
 
-    /// Black square (QED).
-    Qed,
+    /// Black square (QED), with its location for errors.
+    Qed (Span),
 
-    /// End math block.
-    EndMath,
+    /// End of a math block, outside of any math block, with its
+    /// location for errors.
+    EndMath (Span),
+
+    /// Table of contents (nav.toc).
+    Toc {
+        /// Number of header levels to display.
+        depth: usize,
+
+        /// Level, text, and anchor ID of each header displayed.
+        entries: Vec<(usize, String, String)>,
+    },
 
     /// Notice banner (div.notice).
-    Notice (String),
+    Notice (Vec<Expression>),
+
+    /// Fenced code block (pre + code).
+    CodeBlock {
+        /// Language of the code, if provided.
+        language: Option<String>,
+
+        /// Verbatim contents of the block.
+        code: String,
+    },
+
+    /// Raw HTML block, emitted exactly as written.
+    RawHtml (String),
+
+    /// Expression defined outside of the Blog Builder.
+    Custom {
+        /// Name of the expression, for debugging.
+        control: String,
+
+        /// HTML, emitted exactly as written.
+        html: String,
+
+        /// Body of a block, displayed in place of its body placeholder.
+        body: Option<Vec<Expression>>,
+
+        /// Is this expression displayed within a line of text?
+        inline: bool,
+    },
+
+    /// Contents of another source file.
+    /// 
+    /// The parser records only the path; the included expressions
+    /// are filled in by the converter.
+    Include {
+        /// Path of the file, relative to the source directory.
+        path: String,
+
+        /// Expressions of the file.
+        expressions: Vec<Expression>,
+    },
 
     /// Newline.
     Newline,
@@ -118,8 +422,8 @@ pub enum Expression {
     /// Menu.
     Menu,
 
-    /// Parsing error.
-    Error (ParseError),
+    /// Parsing error, with the location of the offending token.
+    Error (ParseError, Span),
 }
 
 // Used for error handling.
@@ -127,17 +431,18 @@ impl Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Expression::*;
         let output = match self {
-            H1 (s) => format!("[h1] {}", s),
-            H2 (s) => format!("[h2] {}", s),
-            H3 (s) => format!("[h3] {}", s),
-            H4 (s) => format!("[h4] {}", s),
-            H5 (s) => format!("[h5] {}", s),
-            H6 (s) => format!("[h6] {}", s),
+            H1 { text, .. } => format!("[h1] {}", text),
+            H2 { text, .. } => format!("[h2] {}", text),
+            H3 { text, .. } => format!("[h3] {}", text),
+            H4 { text, .. } => format!("[h4] {}", text),
+            H5 { text, .. } => format!("[h5] {}", text),
+            H6 { text, .. } => format!("[h6] {}", text),
             Byline {
                 author,
                 date,
             } => format!("[byline] [{}][{}]", author, date),
             Text (s) => s.to_string(),
+            Code (s) => format!("`{}`", s),
             Paragraph (l) => {
                 let mut output = String::new();
 
@@ -147,37 +452,173 @@ impl Display for Expression {
 
                 output
             },
+            UnorderedList (l) => {
+                let mut output = String::from("[ul]");
+
+                for expr in l {
+                    output.push_str(&format!(" [{}]", expr));
+                }
+
+                output
+            },
+            OrderedList { items: l, .. } => {
+                let mut output = String::from("[ol]");
+
+                for expr in l {
+                    output.push_str(&format!(" [{}]", expr));
+                }
+
+                output
+            },
+            ListItem (l) => {
+                let mut output = String::new();
+
+                for expr in l {
+                    output.push_str(&expr.to_string());
+                }
+
+                output
+            },
+            Table {
+                header,
+                ..
+            } => {
+                let mut output = String::from("[table]");
+
+                for expr in header {
+                    output.push_str(&format!(" [{}]", expr));
+                }
+
+                output
+            },
+            Blockquote (l) => {
+                let mut output = String::from("[blockquote]");
+
+                for expr in l {
+                    output.push_str(&format!(" [{}]", expr));
+                }
+
+                output
+            },
             Href {
                 text,
                 href,
-            } => format!("[{}]({})", text, href),
+            } => format!("[{}]({})", inline(text), href),
+            LinkRef {
+                text,
+                label,
+                ..
+            } => format!("[{}][{}]", inline(text), label),
+            LinkDef {
+                label,
+                href,
+                ..
+            } => format!("[{}]: {}", label, href),
+            FootnoteRef {
+                label,
+                ..
+            } => format!("[^{}]", label),
+            Ref {
+                label,
+                ..
+            } => format!("[ref] [{}]", label),
+            Math {
+                tex,
+                display,
+                ..
+            } => if *display {
+                format!("\\[{}\\]", tex)
+            } else {
+                format!("\\({}\\)", tex)
+            },
+            Footnote {
+                label,
+                text,
+                ..
+            } => {
+                let mut output = format!("[^{}]:", label);
+
+                for expr in text {
+                    output.push_str(&expr.to_string());
+                }
+
+                output
+            },
+            Sidenote {
+                number,
+                ..
+            } => format!("[sidenote] [{}]", number),
             FullImage {
                 alt,
                 href,
+                ..
             } => format!("[full-image] [{}][{}]", alt, href),
             FloatImage {
                 alt,
                 href,
+                ..
             } => format!("[float-image] [{}][{}]", alt, href),
             Tile {
                 title,
                 description,
                 href,
                 image,
+                ..
             } => format!("[tile] [{}][{}][{}][{}]", title, description, href, image),
             MathBlock {
                 blocktype,
                 title,
-            } => format!("[mathblock] [{}][{}]", blocktype, title),
-            EndMath => "[endmath]".to_string(),
-            Qed => "[qed]".to_string(),
-            Notice (message) => format!("[notice] [{}]", message),
+                body,
+                ..
+            } => {
+                let mut output = format!("[mathblock] [{}][{}]", blocktype, title);
+
+                for expr in body {
+                    output.push_str(&format!(" [{}]", expr));
+                }
+
+                output
+            },
+            EndMath (_) => "[endmath]".to_string(),
+            Toc { depth, .. } => format!("[toc] [{}]", depth),
+            Qed (_) => "[qed]".to_string(),
+            Notice (l) => {
+                let mut output = String::from("[notice]");
+
+                for expr in l {
+                    output.push_str(&format!(" [{}]", expr));
+                }
+
+                output
+            },
+            CodeBlock {
+                language,
+                ..
+            } => format!("[codeblock] [{}]", language.as_deref().unwrap_or_default()),
+            RawHtml (_) => "[html]".to_string(),
+            Custom {
+                control,
+                body,
+                ..
+            } => {
+                let mut output = format!("[{}]", control);
+
+                for expr in body.iter().flatten() {
+                    output.push_str(&format!(" [{}]", expr));
+                }
+
+                output
+            },
+            Include {
+                path,
+                ..
+            } => format!("[include] [{}]", path),
             Newline => "[newline]".to_string(),
             Menu => "[menu]".to_string(),
-            Bold (s) => format!("**{}**", s),
-            Italics (s) => format!("_{}_", s),
-            BoldItalics (s) => format!("**_{}_**", s),
-            Error (e) => e.to_string(),
+            Bold (l) => format!("**{}**", inline(l)),
+            Italics (l) => format!("_{}_", inline(l)),
+            BoldItalics (l) => format!("**_{}_**", inline(l)),
+            Error (e, _) => e.to_string(),
         };
 
         write!(f, "{}", output)
@@ -185,6 +626,177 @@ impl Display for Expression {
 }
 
 impl Expression {
+    /// Get the expressions nested directly inside of this expression.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `Vec<&Expression>` containing the nested expressions.
+    pub fn children(&self) -> Vec<&Expression> {
+        use Expression::*;
+        match self {
+            Paragraph (l)
+                | UnorderedList (l)
+                | OrderedList { items: l, .. }
+                | ListItem (l)
+                | Blockquote (l)
+                | Bold (l)
+                | Italics (l)
+                | BoldItalics (l)
+                | Href { text: l, .. }
+                | LinkRef { text: l, .. }
+                | Footnote { text: l, .. }
+                | Sidenote { text: l, .. }
+                | Include { expressions: l, .. }
+                | Notice (l)
+                | MathBlock { body: l, .. }
+                | Custom { body: Some (l), .. } => l.iter().collect(),
+            Table {
+                header,
+                rows,
+                ..
+            } => header.iter().chain(rows.iter().flatten()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get mutable references to the expressions nested directly inside
+    /// of this expression.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `Vec<&mut Expression>` containing the nested expressions.
+    pub fn children_mut(&mut self) -> Vec<&mut Expression> {
+        use Expression::*;
+        match self {
+            Paragraph (l)
+                | UnorderedList (l)
+                | OrderedList { items: l, .. }
+                | ListItem (l)
+                | Blockquote (l)
+                | Bold (l)
+                | Italics (l)
+                | BoldItalics (l)
+                | Href { text: l, .. }
+                | LinkRef { text: l, .. }
+                | Footnote { text: l, .. }
+                | Sidenote { text: l, .. }
+                | Include { expressions: l, .. }
+                | Notice (l)
+                | MathBlock { body: l, .. }
+                | Custom { body: Some (l), .. } => l.iter_mut().collect(),
+            Table {
+                header,
+                rows,
+                ..
+            } => header.iter_mut().chain(rows.iter_mut().flatten()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get mutable references to the lists of expressions nested
+    /// directly inside of this expression.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `Vec<&mut Vec<Expression>>` containing the lists.
+    pub(crate) fn lists_mut(&mut self) -> Vec<&mut Vec<Expression>> {
+        use Expression::*;
+        match self {
+            Paragraph (l)
+                | UnorderedList (l)
+                | OrderedList { items: l, .. }
+                | ListItem (l)
+                | Blockquote (l)
+                | Bold (l)
+                | Italics (l)
+                | BoldItalics (l)
+                | Href { text: l, .. }
+                | LinkRef { text: l, .. }
+                | Footnote { text: l, .. }
+                | Sidenote { text: l, .. }
+                | Include { expressions: l, .. }
+                | Notice (l)
+                | MathBlock { body: l, .. }
+                | Custom { body: Some (l), .. } => vec![l],
+            Table {
+                header,
+                rows,
+                ..
+            } => std::iter::once(header).chain(rows.iter_mut()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Is this an inline expression?
+    /// 
+    /// Inline expressions are displayed within a line of text, and a
+    /// line that begins with one is a paragraph.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `bool` indicating if this expression is inline.
+    pub fn is_inline(&self) -> bool {
+        use Expression::*;
+        matches!(
+            self,
+            Bold (_)
+                | Italics (_)
+                | BoldItalics (_)
+                | Text (_)
+                | Code (_)
+                | Href { .. }
+                | LinkRef { .. }
+                | FootnoteRef { .. }
+                | Ref { .. }
+                | Math { .. }
+                | Custom { inline: true, .. }
+        )
+    }
+
+    /// Apply a function to this expression and to every expression
+    /// nested inside of it, in document order.
+    /// 
+    /// # Parameters
+    /// - `f` (`&mut impl FnMut(&Expression)`): the function
+    /// 
+    /// # Returns
+    /// None.
+    pub fn walk(&self, f: &mut impl FnMut(&Expression)) {
+        f(self);
+
+        for child in self.children() {
+            child.walk(f);
+        }
+    }
+
+    /// Apply a function to this expression and to every expression
+    /// nested inside of it, in document order, allowing each to be
+    /// modified.
+    /// 
+    /// The function is applied to an expression before the expressions
+    /// nested inside of it.
+    /// 
+    /// # Parameters
+    /// - `f` (`&mut impl FnMut(&mut Expression)`): the function
+    /// 
+    /// # Returns
+    /// None.
+    pub fn walk_mut(&mut self, f: &mut impl FnMut(&mut Expression)) {
+        f(self);
+
+        for child in self.children_mut() {
+            child.walk_mut(f);
+        }
+    }
+
     /// Convert the expression to an HTML string.
     /// 
     /// # Parameters
@@ -193,24 +805,27 @@ impl Expression {
     /// # Returns
     /// A `String` with the formatted expression.
     /// 
+    /// **Note**: a `Menu` is emitted as an empty string, because menus
+    /// are inserted by the emitter, and only at the top level.
+    /// 
     /// # Panics
-    /// This function panics when it tries to emit a `Menu` or `Error` variant
-    ///     because these should be handled by the emitter directly _before_
+    /// This function panics when it tries to emit an `Error` variant
+    ///     because errors should be reported by the checker _before_
     ///     direct conversion to HTML.
     pub fn html(&self, top: bool) -> String {
         use Expression::*;
         match self {
-            H1 (s) => format!("<h1>{}</h1>", s),
-            H2 (s) => format!("<h2>{}</h2>", s),
-            H3 (s) => format!("<h3>{}</h3>", s),
-            H4 (s) => format!("<h4>{}</h4>", s),
-            H5 (s) => format!("<h5>{}</h5>", s),
-            H6 (s) => format!("<h6>{}</h6>", s),
+            H1 { text, id, permalink } => header(1, text, id, *permalink),
+            H2 { text, id, permalink } => header(2, text, id, *permalink),
+            H3 { text, id, permalink } => header(3, text, id, *permalink),
+            H4 { text, id, permalink } => header(4, text, id, *permalink),
+            H5 { text, id, permalink } => header(5, text, id, *permalink),
+            H6 { text, id, permalink } => header(6, text, id, *permalink),
             Byline {
                 author,
                 date,
-            } => format!("<div class=\"byline\"><div>{}</div><div>{}</div></div>", author, date),
-            Text (s) => s.to_string(),
+            } => format!("<div class=\"byline\"><div>{}</div><div>{}</div></div>", escape(author), escape(date)),
+            Text (s) => escape(s),
             Paragraph (l) => {
                 let mut output = String::new();
 
@@ -225,53 +840,427 @@ impl Expression {
                     output.to_string()
                 }
             },
-            Bold (s) => if top {
-                format!("<p><strong>{}</strong></p>", s)
+            UnorderedList (l) => {
+                let mut output = String::new();
+
+                // Format each list item
+                for expr in l {
+                    output.push_str(&expr.html(false));
+                }
+
+                format!("<ul>{}</ul>", output)
+            },
+            OrderedList {
+                start,
+                items,
+            } => {
+                let mut output = String::new();
+
+                // Format each list item
+                for expr in items {
+                    output.push_str(&expr.html(false));
+                }
+
+                // Lists are numbered from one unless told otherwise
+                if *start == 1 {
+                    format!("<ol>{}</ol>", output)
+                } else {
+                    format!("<ol start=\"{}\">{}</ol>", start, output)
+                }
+            },
+            ListItem (l) => {
+                let mut output = String::new();
+
+                // Format each interior expression
+                for expr in l {
+                    output.push_str(&expr.html(false));
+                }
+
+                format!("<li>{}</li>", output)
+            },
+            Table {
+                alignments,
+                header,
+                rows,
+            } => {
+                // Format a row of cells
+                let row = |cells: &[Expression], tag: &str| {
+                    let mut output = String::from("<tr>");
+
+                    for (cell, alignment) in cells.iter().zip(alignments) {
+                        if let Some (a) = alignment {
+                            output.push_str(&format!("<{} style=\"text-align: {};\">{}</{}>", tag, a.css(), cell.html(false), tag));
+                        } else {
+                            output.push_str(&format!("<{}>{}</{}>", tag, cell.html(false), tag));
+                        }
+                    }
+
+                    output.push_str("</tr>");
+                    output
+                };
+
+                let mut output = format!("<table><thead>{}</thead>", row(header, "th"));
+
+                if !rows.is_empty() {
+                    output.push_str("<tbody>");
+
+                    for r in rows {
+                        output.push_str(&row(r, "td"));
+                    }
+
+                    output.push_str("</tbody>");
+                }
+
+                output.push_str("</table>");
+                output
+            },
+            Blockquote (l) => {
+                let mut output = String::new();
+
+                // Format each interior expression as a block
+                for expr in l {
+                    output.push_str(&expr.html(true));
+                }
+
+                format!("<blockquote>{}</blockquote>", output)
+            },
+            Bold (l) => if top {
+                format!("<p><strong>{}</strong></p>", inline_html(l))
+            } else {
+                format!("<strong>{}</strong>", inline_html(l))
+            },
+            Italics (l) => if top {
+                format!("<p><em>{}</em></p>", inline_html(l))
             } else {
-                format!("<strong>{}</strong>", s)
+                format!("<em>{}</em>", inline_html(l))
             },
-            Italics (s) => if top {
-                format!("<p><em>{}</em></p>", s)
+            BoldItalics (l) => if top {
+                format!("<p><strong><em>{}</em></strong></p>", inline_html(l))
             } else {
-                format!("<em>{}</em>", s)
+                format!("<strong><em>{}</em></strong>", inline_html(l))
             },
-            BoldItalics (s) => if top {
-                format!("<p><strong><em>{}</em></strong></p>", s)
+            Code (s) => if top {
+                format!("<p><code>{}</code></p>", escape(s))
             } else {
-                format!("<strong><em>{}</em></strong>", s)
+                format!("<code>{}</code>", escape(s))
             },
             Href {
                 text,
                 href,
             } => if top {
-                format!("<p><a href=\"{}\">{}</a></p>", href, text)
+                format!("<p><a href=\"{}\">{}</a></p>", escape_uri(href), inline_html(text))
             } else {
-                format!("<a href=\"{}\">{}</a>", href, text)
+                format!("<a href=\"{}\">{}</a>", escape_uri(href), inline_html(text))
+            },
+            LinkRef {
+                text,
+                href,
+                title,
+                ..
+            } => {
+                let title = if title.is_empty() {
+                    String::new()
+                } else {
+                    format!(" title=\"{}\"", escape(title))
+                };
+
+                let output = format!("<a href=\"{}\"{}>{}</a>", escape_uri(href), title, inline_html(text));
+
+                if top {
+                    format!("<p>{}</p>", output)
+                } else {
+                    output
+                }
+            },
+            // Definitions only supply the URIs of references
+            LinkDef { .. } => String::new(),
+            FootnoteRef {
+                number,
+                first,
+                ..
+            } => {
+                // Only the first reference can be linked back to
+                let id = if *first {
+                    format!(" id=\"fnref-{}\"", number)
+                } else {
+                    String::new()
+                };
+
+                let output = format!("<sup class=\"footnote-ref\"><a href=\"#fn-{}\"{}>{}</a></sup>", number, id, number);
+
+                if top {
+                    format!("<p>{}</p>", output)
+                } else {
+                    output
+                }
+            },
+            Ref {
+                text,
+                href,
+                ..
+            } => {
+                let output = format!("<a class=\"ref\" href=\"{}\">{}</a>", escape_uri(href), escape(text));
+
+                if top {
+                    format!("<p>{}</p>", output)
+                } else {
+                    output
+                }
+            },
+            Math {
+                mathml,
+                ..
+            } => if top {
+                format!("<p>{}</p>", mathml)
+            } else {
+                mathml.to_string()
+            },
+            // Definitions are displayed by the emitter at the end of
+            //  the page, not where they are written
+            Footnote { .. } => String::new(),
+            Sidenote {
+                number,
+                text,
+            } => {
+                let mut output = String::new();
+
+                // Format each interior expression
+                for expr in text {
+                    output.push_str(&expr.html(false));
+                }
+
+                let output = format!("<sup class=\"sidenote-number\">{}</sup><span class=\"sidenote\"><sup>{}</sup> {}</span>", number, number, output);
+
+                if top {
+                    format!("<p>{}</p>", output)
+                } else {
+                    output
+                }
             },
             FullImage {
                 alt,
                 href,
-            } => format!("<img class=\"full\" src=\"{}\" alt=\"{}\">", href, alt),
+                width,
+                caption,
+            } => image("full", alt, href, width, caption),
             FloatImage {
                 alt,
                 href,
-            } => format!("<img class=\"float\" src=\"{}\" alt=\"{}\">", href, alt),
+                width,
+                caption,
+            } => image("float", alt, href, width, caption),
             Tile {
                 title,
                 description,
                 href,
                 image,
-            } => format!("<div class=\"tile\" onclick=\"window.location='{}';\" style=\"background-image: url('{}'); cursor: pointer; background-position: center;\"><div>{}</div><br><div class=\"desc\">{}</div></div>", href, image, title, description),
+                target,
+            } => format!("<div class=\"tile\" onclick=\"{}\" style=\"background-image: url('{}'); cursor: pointer; background-position: center;\"><div>{}</div><br><div class=\"desc\">{}</div></div>",
+                // The link and image are strings inside of JavaScript and CSS
+                if let Some (t) = target {
+                    format!("window.open('{}', '{}');", escape_string(safe_uri(href)), escape_string(t))
+                } else {
+                    format!("window.location='{}';", escape_string(safe_uri(href)))
+                },
+                escape_string(safe_uri(image)),
+                escape(title),
+                escape(description),
+            ),
             MathBlock {
                 blocktype,
                 title,
-            } => format!("<div class=\"mathblock\"><span>{}. {}</span>", blocktype, title),
-            EndMath => "</div>".to_string(),
-            Qed => "<p>&#x25A0;</p>".to_string(),
-            Notice (message) => format!("<div class=\"notice\">{}</div>", message),
+                label,
+                number,
+                body,
+            } => {
+                let id = if let Some (l) = label {
+                    format!(" id=\"{}\"", escape(l))
+                } else {
+                    String::new()
+                };
+
+                // Unnumbered blocks display only their type
+                let name = if number.is_empty() {
+                    escape(blocktype)
+                } else {
+                    format!("{} {}", escape(blocktype), escape(number))
+                };
+
+                let mut output = format!("<div class=\"mathblock\"{}><span>{}. {}</span>", id, name, escape(title));
+
+                for expr in body {
+                    output.push_str(&expr.html(true));
+                }
+
+                output.push_str("</div>");
+                output
+            },
+            EndMath (_) => String::new(),
+            Toc { entries, .. } => toc(entries),
+            Qed (_) => "<p>&#x25A0;</p>".to_string(),
+            Notice (l) => {
+                let mut output = String::from("<div class=\"notice\">");
+
+                for expr in l {
+                    output.push_str(&expr.html(true));
+                }
+
+                output.push_str("</div>");
+                output
+            },
+            CodeBlock {
+                language,
+                code,
+            } => if let Some (l) = language {
+                format!("<pre><code class=\"language-{}\">{}</code></pre>", escape(l), escape(code))
+            } else {
+                format!("<pre><code>{}</code></pre>", escape(code))
+            },
+            RawHtml (s) => s.to_string(),
+            Custom {
+                html,
+                body: Some (l),
+                ..
+            } => {
+                let body: String = l.iter().map(|e| e.html(true)).collect();
+
+                match html.split_once(BODY_PLACEHOLDER) {
+                    Some ((before, after)) => format!("{}{}{}", before, body, after),
+                    None => format!("{}{}", html, body),
+                }
+            },
+            Custom {
+                html,
+                ..
+            } => html.to_string(),
+            Include {
+                expressions,
+                ..
+            } => expressions.iter().map(|e| e.html(top)).collect(),
             Newline => "\n\n".to_string(),
-            Menu => unreachable!(),
-            Error (_) => unreachable!(),
+            Menu => String::new(),
+            Error (..) => unreachable!(),
         }
     }
 }
+
+/// Display a header.
+/// 
+/// # Parameters
+/// - `level` (`usize`): the level of the header, from 1 to 6
+/// - `text` (`&str`): the header text
+/// - `id` (`&str`): the anchor ID, or empty if none
+/// - `permalink` (`bool`): whether to display a permalink
+/// 
+/// # Returns
+/// A `String` containing HTML.
+fn header(level: usize, text: &str, id: &str, permalink: bool) -> String {
+    if id.is_empty() {
+        return format!("<h{}>{}</h{}>", level, escape(text), level);
+    }
+
+    let link = if permalink {
+        format!("<a class=\"permalink\" href=\"#{}\" aria-label=\"Permalink\">#</a>", escape(id))
+    } else {
+        String::new()
+    };
+
+    format!("<h{} id=\"{}\">{}{}</h{}>", level, escape(id), escape(text), link, level)
+}
+
+/// Display a table of contents as nested lists.
+/// 
+/// # Parameters
+/// - `entries` (`&[(usize, String, String)]`): the level, text, and
+///   anchor ID of each header
+/// 
+/// # Returns
+/// A `String` containing HTML.
+fn toc(entries: &[(usize, String, String)]) -> String {
+    let mut output = String::from("<nav class=\"toc\">");
+
+    // Levels of the lists currently open
+    let mut open: Vec<usize> = Vec::new();
+
+    for (level, text, id) in entries {
+        // Close any lists deeper than this header
+        while open.last().is_some_and(|l| l > level) {
+            output.push_str("</li></ul>");
+            open.pop();
+        }
+
+        if open.last() == Some (level) {
+            output.push_str("</li>");
+        } else {
+            output.push_str("<ul>");
+            open.push(*level);
+        }
+
+        output.push_str(&format!("<li><a href=\"#{}\">{}</a>", escape(id), escape(text)));
+    }
+
+    for _ in open {
+        output.push_str("</li></ul>");
+    }
+
+    output.push_str("</nav>");
+    output
+}
+
+/// Display an image as HTML.
+/// 
+/// Images with captions are placed in a figure, which takes the
+/// class and width of the image.
+/// 
+/// # Parameters
+/// - `class` (`&str`): the class of the image
+/// - `alt` (`&str`): the alternate text
+/// - `href` (`&str`): the URI of the image
+/// - `width` (`&Option<String>`): the CSS width of the image
+/// - `caption` (`&Option<String>`): the caption
+/// 
+/// # Returns
+/// A `String` containing HTML.
+fn image(class: &str, alt: &str, href: &str, width: &Option<String>, caption: &Option<String>) -> String {
+    let style = if let Some (w) = width {
+        format!(" style=\"width: {};\"", escape(w))
+    } else {
+        String::new()
+    };
+
+    if let Some (c) = caption {
+        format!(
+            "<figure class=\"{}\"{}><img class=\"{}\" src=\"{}\" alt=\"{}\"><figcaption>{}</figcaption></figure>",
+            class,
+            style,
+            class,
+            escape_uri(href),
+            escape(alt),
+            escape(c),
+        )
+    } else {
+        format!("<img class=\"{}\" src=\"{}\" alt=\"{}\"{}>", class, escape_uri(href), escape(alt), style)
+    }
+}
+
+/// Display a list of inline expressions as Markdown.
+/// 
+/// # Parameters
+/// - `l` (`&[Expression]`): the list of expressions
+/// 
+/// # Returns
+/// A `String` containing the expressions.
+fn inline(l: &[Expression]) -> String {
+    l.iter().map(|e| e.to_string()).collect()
+}
+
+/// Convert a list of inline expressions to HTML.
+/// 
+/// # Parameters
+/// - `l` (`&[Expression]`): the list of expressions
+/// 
+/// # Returns
+/// A `String` containing HTML.
+fn inline_html(l: &[Expression]) -> String {
+    l.iter().map(|e| e.html(false)).collect()
+}
@@ -0,0 +1,40 @@
+//! Footnote numbering for the Blog Builder.
+
+use std::collections::HashMap;
+
+use crate::{
+    Expression,
+    Node,
+};
+
+/// Number the footnotes in a list of nodes.
+/// 
+/// Footnotes are numbered in the order in which they are first
+/// referenced, regardless of where they are defined.  Definitions
+/// that are never referenced are left with number zero.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// 
+/// # Returns
+/// None.
+pub fn number(nodes: &mut [Node]) {
+    // Map from labels to numbers
+    let mut numbers: HashMap<String, usize> = HashMap::new();
+
+    // Number the references
+    for node in nodes.iter_mut() {
+        node.expression.walk_mut(&mut |expr| if let Expression::FootnoteRef { label, number, first, .. } = expr {
+            let next = numbers.len() + 1;
+            *number = *numbers.entry(label.to_owned()).or_insert(next);
+            *first = *number == next;
+        });
+    }
+
+    // Number the definitions
+    for node in nodes.iter_mut() {
+        node.expression.walk_mut(&mut |expr| if let Expression::Footnote { label, number, .. } = expr {
+            *number = numbers.get(label).copied().unwrap_or(0);
+        });
+    }
+}
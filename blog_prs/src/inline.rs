@@ -0,0 +1,122 @@
+//! Inline syntax defined outside of the Blog Builder.
+
+use crate::{
+    Expression,
+    Inline,
+    Node,
+};
+
+/// Apply inline syntax to the text in a list of nodes.
+/// 
+/// # Parameters
+/// - `rules` (`&[Box<dyn Inline>]`): the inline syntax
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// 
+/// # Returns
+/// None.
+pub fn apply(rules: &[Box<dyn Inline>], nodes: &mut [Node]) {
+    if rules.is_empty() {
+        return;
+    }
+
+    for node in nodes {
+        apply_expr(rules, &mut node.expression);
+    }
+}
+
+/// Apply inline syntax to the text in an expression.
+/// 
+/// # Parameters
+/// - `rules` (`&[Box<dyn Inline>]`): the inline syntax
+/// - `expression` (`&mut Expression`): the expression
+/// 
+/// # Returns
+/// None.
+fn apply_expr(rules: &[Box<dyn Inline>], expression: &mut Expression) {
+    for list in expression.lists_mut() {
+        // Split each text around the inline syntax
+        for expr in join(list) {
+            match expr {
+                Expression::Text (t) => split(rules, &t, list),
+                mut e => {
+                    apply_expr(rules, &mut e);
+                    list.push(e);
+                },
+            }
+        }
+    }
+}
+
+/// Remove the expressions from a list, joining adjacent text.
+/// 
+/// Text is split wherever a token ends, so it must be joined back
+/// together before searching it for syntax that spans several tokens.
+/// 
+/// # Parameters
+/// - `list` (`&mut Vec<Expression>`): the list of expressions, which
+///   is left empty
+/// 
+/// # Returns
+/// A `Vec<Expression>` containing the joined expressions.
+pub(crate) fn join(list: &mut Vec<Expression>) -> Vec<Expression> {
+    let mut joined: Vec<Expression> = Vec::new();
+
+    for expr in list.drain(..) {
+        match (joined.last_mut(), expr) {
+            (Some (Expression::Text (a)), Expression::Text (b)) => a.push_str(&b),
+            (_, e) => joined.push(e),
+        }
+    }
+
+    joined
+}
+
+/// Split text around the inline syntax in it.
+/// 
+/// # Parameters
+/// - `rules` (`&[Box<dyn Inline>]`): the inline syntax
+/// - `text` (`&str`): the text
+/// - `output` (`&mut Vec<Expression>`): the list of expressions to
+///   which the pieces of the text are added
+/// 
+/// # Returns
+/// None.
+fn split(rules: &[Box<dyn Inline>], text: &str, output: &mut Vec<Expression>) {
+    let mut rest = text;
+
+    loop {
+        // Find the first complete use of any syntax
+        let first = rules.iter()
+            .filter_map(|r| {
+                let (open, close) = r.delimiters();
+                let start = rest.find(open)?;
+                let end = rest[start + open.len()..].find(close)? + start + open.len();
+
+                Some ((start, end, r, open.len(), close.len()))
+            })
+            .min_by_key(|(start, ..)| *start);
+
+        let (start, end, rule, open, close) = if let Some (f) = first {
+            f
+        } else {
+            break;
+        };
+
+        if start > 0 {
+            output.push(Expression::Text (rest[..start].to_owned()));
+        }
+
+        output.push(Expression::Custom {
+            control: rest[start..start + open].to_owned(),
+            html: rule.emit(&rest[start + open..end]),
+            body: None,
+            inline: true,
+        });
+
+        rest = &rest[end + close..];
+    }
+
+    if !rest.is_empty() {
+        output.push(Expression::Text (rest.to_owned()));
+    }
+}
@@ -6,16 +6,58 @@
 // Enforce all documentation.
 #![deny(missing_docs)]
 
+mod anchors;
+mod custom;
+mod equations;
 mod error;
+mod escape;
 mod expression;
+mod footnotes;
+mod inline;
+mod links;
+mod mathblocks;
+mod node;
 mod parselet;
-mod parselets;
+pub mod parselets;
 mod parser;
 
-pub use expression::Expression;
+pub use expression::{
+    Alignment,
+    Expression,
+};
+
+pub use anchors::{
+    assign as assign_anchors,
+    slugify,
+};
+
+pub use custom::{
+    BODY_PLACEHOLDER,
+    Control,
+    CustomControl,
+    Inline,
+};
+
+pub use equations::render as render_equations;
 
 pub use error::ParseError;
 
+pub use footnotes::number as number_footnotes;
+
+pub use links::resolve as resolve_links;
+
+pub use mathblocks::{
+    number as number_blocks,
+    resolve as resolve_refs,
+};
+
+pub use escape::{
+    escape,
+    escape_uri,
+};
+
+pub use node::Node;
+
 pub use parselet::Parselet;
 
 pub use parser::Parser;
\ No newline at end of file
@@ -0,0 +1,55 @@
+//! Reference-style links for the Blog Builder.
+
+use std::collections::HashMap;
+
+use crate::{
+    Expression,
+    Node,
+};
+
+/// Fill in the URI and title of each reference-style link in a list of
+/// nodes from the link reference definitions among them.
+/// 
+/// Labels are matched without regard to case or to the spacing between
+/// words.  If a label is defined more than once, the first definition
+/// is used.  Links to undefined labels are left unresolved, to be
+/// reported by the checker.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// 
+/// # Returns
+/// None.
+pub fn resolve(nodes: &mut [Node]) {
+    // URI and title of each label
+    let mut definitions: HashMap<String, (String, String)> = HashMap::new();
+    for node in nodes.iter() {
+        node.expression.walk(&mut |expr| if let Expression::LinkDef { label, href, title } = expr {
+            definitions.entry(normalize(label)).or_insert_with(|| (href.to_owned(), title.to_owned()));
+        });
+    }
+
+    for node in nodes.iter_mut() {
+        node.expression.walk_mut(&mut |expr| if let Expression::LinkRef { label, href, title, .. } = expr {
+            if let Some ((h, t)) = definitions.get(&normalize(label)) {
+                *href = h.to_owned();
+                *title = t.to_owned();
+            }
+        });
+    }
+}
+
+/// Normalize a label for matching.
+/// 
+/// # Parameters
+/// - `label` (`&str`): the label
+/// 
+/// # Returns
+/// A `String` containing the label in lowercase, with single spaces
+/// between words.
+fn normalize(label: &str) -> String {
+    label.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
@@ -0,0 +1,93 @@
+//! Math block numbering and cross-references for the Blog Builder.
+
+use std::collections::HashMap;
+
+use crate::{
+    Expression,
+    Node,
+};
+
+/// Number the math blocks in a list of nodes.
+/// 
+/// Each type of block is numbered on its own, so that a page may have
+/// both Theorem 1 and Lemma 1.  When numbered by section, numbers
+/// restart after each `H2` header and begin with the number of the
+/// section, as in Theorem 2.3.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// - `sections` (`bool`): whether to number blocks by section
+/// - `numbered` (`impl Fn(&str) -> bool`): whether to number blocks
+///   of a given type
+/// 
+/// # Returns
+/// A `Vec<(String, String)>` containing the label and the display
+/// text (such as `Theorem 2.3`) of each labelled block, in order.
+pub fn number(nodes: &mut [Node], sections: bool, numbered: impl Fn(&str) -> bool) -> Vec<(String, String)> {
+    // Number of blocks of each type so far
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    // Number of the current section
+    let mut section = 0;
+
+    // Labels of blocks and their display text
+    let mut labels = Vec::new();
+
+    for node in nodes.iter_mut() {
+        node.expression.walk_mut(&mut |expr| match expr {
+            Expression::H2 { .. } if sections => {
+                section += 1;
+                counts.clear();
+            },
+            Expression::MathBlock { blocktype, label, number, .. } => {
+                if numbered(blocktype) {
+                    let count = counts.entry(blocktype.to_lowercase()).or_insert(0);
+                    *count += 1;
+
+                    *number = if sections {
+                        format!("{}.{}", section, count)
+                    } else {
+                        count.to_string()
+                    };
+                }
+
+                if let Some (l) = label {
+                    let text = if number.is_empty() {
+                        blocktype.to_owned()
+                    } else {
+                        format!("{} {}", blocktype, number)
+                    };
+
+                    labels.push((l.to_owned(), text));
+                }
+            },
+            _ => (),
+        });
+    }
+
+    labels
+}
+
+/// Fill in the text and location of each cross-reference in a list of
+/// nodes.
+/// 
+/// Cross-references to unknown labels are left unresolved, to be
+/// reported by the checker.
+/// 
+/// # Parameters
+/// - `nodes` (`&mut [Node]`): the list of nodes
+/// - `targets` (`&HashMap<String, (String, String)>`): the display text
+///   and URI of each label
+/// 
+/// # Returns
+/// None.
+pub fn resolve(nodes: &mut [Node], targets: &HashMap<String, (String, String)>) {
+    for node in nodes.iter_mut() {
+        node.expression.walk_mut(&mut |expr| if let Expression::Ref { label, text, href, .. } = expr {
+            if let Some ((t, h)) = targets.get(label) {
+                *text = t.to_owned();
+                *href = h.to_owned();
+            }
+        });
+    }
+}
@@ -0,0 +1,15 @@
+//! Located expressions for the Blog Builder.
+
+use blog_tkn::Span;
+
+use crate::Expression;
+
+#[derive(PartialEq, Clone, Debug)]
+/// A top-level expression along with its location in the source.
+pub struct Node {
+    /// The expression.
+    pub expression: Expression,
+
+    /// Location of the first token in the expression.
+    pub span: Span,
+}
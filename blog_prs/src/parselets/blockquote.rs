@@ -0,0 +1,83 @@
+//! Blockquote parselet.
+
+use blog_tkn::{
+    Token,
+    TokenClass,
+    Tokenizer,
+};
+
+use crate::{
+    Parser,
+    Parselet,
+    Expression,
+};
+
+/// Parselet for blockquotes.
+pub struct BlockquoteParselet { }
+
+impl Parselet for BlockquoteParselet {
+    fn parse(&self, parser: &Parser, tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+        parse_quote(parser, tokenizer, 1, token.value.len())
+    }
+}
+
+/// Parse a blockquote at a given nesting depth.
+/// 
+/// The marker of the first line has already been consumed, and it
+/// has nesting depth `line`.  Each line of the blockquote is parsed
+/// as a block expression.  The blockquote ends at the first line
+/// whose marker is shallower than `depth`.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// - `depth` (`usize`): the nesting depth of this blockquote
+/// - `line` (`usize`): the nesting depth of the first line
+/// 
+/// # Returns
+/// An `Expression` containing the blockquote.
+fn parse_quote(parser: &Parser, tokenizer: &mut Tokenizer, depth: usize, line: usize) -> Expression {
+    // Initialize list of expressions
+    let mut output = Vec::new();
+
+    // Record the depth so that nested expressions can continue across lines
+    let outer = parser.set_quote_depth(depth);
+
+    let mut line = line;
+    loop {
+        if line > depth {
+            // This line begins a nested blockquote
+            output.push(parse_quote(parser, tokenizer, depth + 1, line));
+        } else if let Some (t) = tokenizer.peek() {
+            if t.class == TokenClass::Newline {
+                // An empty line separates paragraphs
+                let _ = tokenizer.next();
+                output.push(Expression::Newline);
+            } else {
+                // Parse the contents of this line
+                output.push(parser.parse_block(tokenizer));
+
+                // Consume the end of the line, if it's still there
+                if !tokenizer.at_line_start() && tokenizer.peek().map(|t| t.class) == Some (TokenClass::Newline) {
+                    let _ = tokenizer.next();
+                }
+            }
+        } else {
+            // We're at EOF
+            break;
+        }
+
+        // Continue if the next line belongs to this blockquote
+        match tokenizer.peek() {
+            Some (t) if t.class == TokenClass::Quote && t.value.len() >= depth => {
+                let _ = tokenizer.next();
+                line = t.value.len();
+            },
+            _ => break,
+        }
+    }
+
+    parser.set_quote_depth(outer);
+
+    Expression::Blockquote (output)
+}
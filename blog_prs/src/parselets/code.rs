@@ -0,0 +1,22 @@
+//! Inline code parselet.
+
+use blog_tkn::{
+    Token,
+    Tokenizer,
+};
+
+use crate::{
+    Parser,
+    Parselet,
+    Expression,
+};
+
+/// Parselet for inline code.
+pub struct CodeParselet { }
+
+impl Parselet for CodeParselet {
+    fn parse(&self, _parser: &Parser, _tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+        // The tokenizer has already read the code verbatim
+        Expression::Code (token.value.to_owned())
+    }
+}
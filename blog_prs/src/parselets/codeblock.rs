@@ -0,0 +1,32 @@
+//! Fenced code block parselet.
+
+use blog_tkn::{
+    Token,
+    Tokenizer,
+};
+
+use crate::{
+    Parser,
+    Parselet,
+    Expression,
+};
+
+/// Parselet for fenced code blocks.
+pub struct CodeBlockParselet { }
+
+impl Parselet for CodeBlockParselet {
+    fn parse(&self, _parser: &Parser, _tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+        // The first line of the token is the info string
+        let (info, code) = token.value.split_once('\n').unwrap_or((&token.value, ""));
+
+        // The language is the first word of the info string
+        let language = info.split_whitespace()
+            .next()
+            .map(|l| l.to_owned());
+
+        Expression::CodeBlock {
+            language,
+            code: code.to_owned(),
+        }
+    }
+}
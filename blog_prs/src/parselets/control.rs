@@ -1,6 +1,9 @@
 //! Control sequence parselet.
 
+use std::collections::HashMap;
+
 use blog_tkn::{
+    Span,
     Token,
     TokenClass,
     Tokenizer,
@@ -13,23 +16,32 @@ use crate::{
     ParseError,
 };
 
+use super::paragraph::{
+    parse_inline,
+    raw_text,
+};
+
+/// Number of header levels displayed by a table of contents, unless
+/// given.
+const DEFAULT_TOC_DEPTH: usize = 3;
+
 /// Parselet for control sequences.
 pub struct ControlParselet { }
 
 impl Parselet for ControlParselet {
-    fn parse(&self, _parser: &Parser, tokenizer: &mut Tokenizer, _token: &Token) -> Expression {
+    fn parse(&self, parser: &Parser, tokenizer: &mut Tokenizer, token: &Token) -> Expression {
         // Get control sequence type
-        let ctrl = if let Some (opt_t) = tokenizer.expect(TokenClass::Paragraph) {
+        let (ctrl, span) = if let Some (opt_t) = tokenizer.expect(TokenClass::Paragraph) {
             if let Some (t) = opt_t {
                 // Make sure to trim the value
-                t.value.trim().to_owned()
+                (t.value.trim().to_owned(), t.span)
             } else {
                 // Expected text, found something else
-                return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph));
+                return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph), tokenizer.span());
             }
         } else {
             // We ran out of tokens :(
-            return Expression::Error (ParseError::UnexpectedEof);
+            return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
         };
 
         // Initialize list of values
@@ -56,38 +68,185 @@ impl Parselet for ControlParselet {
             }
         }
 
-        build_expr(&ctrl, values)
+        // Parse the body of a block control sequence
+        let body = if let Some (ends) = block_ends(parser, &ctrl, &values, tokenizer) {
+            match parse_body(parser, tokenizer, &ctrl, ends, span) {
+                Ok (b) => Some (b),
+                Err (e) => return e,
+            }
+        } else {
+            None
+        };
+
+        let mut expr = build_expr(parser, &ctrl, values, body, span);
+
+        // Cross-references are located by their name and label
+        if let Expression::Ref { span: s, .. } = &mut expr {
+            *s = s.to(tokenizer.span());
+        }
+
+        // A cross-reference at the start of a line begins a paragraph
+        if token.span.column == 1 && matches!(expr, Expression::Ref { .. }) {
+            let mut output = vec![expr];
+            parse_inline(parser, tokenizer, &mut output);
+
+            return Expression::Paragraph (output);
+        }
+
+        expr
     }
 }
 
-/// Build a control sequence expression from a list of values.
-fn build_expr(ctrl: &str, values: Vec<String>) -> Expression {
-    // How long should the list be?
-    let len: usize = match ctrl {
-        "image" => 2,
-        "byline" => 2,
-        "float" => 2,
-        "notice" => 1,
-        "tile" => 4,
-        "mathblock" => 2,
-        "endmath" => 0,
-        "qed" => 0,
-        _ => return Expression::Error (ParseError::UnrecognizedControl (ctrl.to_owned())),
+/// Check if a control sequence opens a block.
+/// 
+/// Control sequences defined outside of the Blog Builder open a block
+/// if they say so.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `ctrl` (`&str`): the name of the control sequence
+/// - `values` (`&[String]`): the bracketed arguments
+/// - `tokenizer` (`&Tokenizer`): the token stream
+/// 
+/// # Returns
+/// An `Option` containing the names of the control sequences that may
+/// close the block, if it is a block.
+fn block_ends(parser: &Parser, ctrl: &str, values: &[String], tokenizer: &Tokenizer) -> Option<&'static [&'static str]> {
+    // Is the rest of the line empty?
+    let alone = tokenizer.peek().is_none_or(|t| t.class == TokenClass::Newline);
+
+    match ctrl {
+        // Math blocks may also be closed by `::endmath`
+        "mathblock" => Some (&["end", "endmath"]),
+        "notice" if values.is_empty() && alone => Some (&["end"]),
+        _ if parser.control(ctrl).is_some_and(|c| c.block()) => Some (&["end"]),
+        _ => None,
+    }
+}
+
+/// Parse the body of a block control sequence.
+/// 
+/// The body ends at a line beginning with one of the given control
+/// sequences, which is consumed.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// - `ctrl` (`&str`): the name of the control sequence
+/// - `ends` (`&[&str]`): the names of the control sequences that
+///   may close the block
+/// - `span` (`Span`): the location of the control sequence name
+/// 
+/// # Returns
+/// A `Result` containing the expressions in the body, or an error
+/// expression if the block is never closed.
+fn parse_body(parser: &Parser, tokenizer: &mut Tokenizer, ctrl: &str, ends: &[&str], span: Span) -> Result<Vec<Expression>, Expression> {
+    let mut body = Vec::new();
+
+    while let Some (t) = tokenizer.peek() {
+        // Is this the end of the block?
+        if t.class == TokenClass::Control && t.span.column == 1 {
+            if let Some (n) = tokenizer.look_ahead(1) {
+                if n.class == TokenClass::Paragraph && ends.contains(&n.value.trim()) {
+                    // Consume the closing control sequence
+                    let _ = tokenizer.next();
+                    let _ = tokenizer.next();
+
+                    return Ok (body);
+                }
+            }
+        }
+
+        let expression = parser.parse_block(tokenizer);
+
+        // Skip the rest of any erroneous expression, as the
+        //  parser does at the top level
+        if let Expression::Error (..) = expression {
+            tokenizer.synchronize();
+        }
+
+        body.push(expression);
+    }
+
+    Err (Expression::Error (ParseError::UnclosedBlock (ctrl.to_owned()), span))
+}
+
+/// Arguments to a control sequence, bound to their names.
+struct Arguments {
+    /// Required arguments, in order.
+    positional: Vec<String>,
+
+    /// Optional arguments, by name.
+    options: HashMap<String, String>,
+}
+
+impl Arguments {
+    /// Get an optional argument.
+    /// 
+    /// # Parameters
+    /// - `name` (`&str`): the name of the argument
+    /// 
+    /// # Returns
+    /// An `Option<String>` containing the argument, if it was given.
+    fn option(&self, name: &str) -> Option<String> {
+        self.options.get(name).cloned()
+    }
+}
+
+/// Build a control sequence expression from a list of values and,
+/// for blocks, a body.
+/// 
+/// Any errors are reported at `span`, the location of the control
+/// sequence name.
+fn build_expr(parser: &Parser, ctrl: &str, values: Vec<String>, body: Option<Vec<Expression>>, span: Span) -> Expression {
+    // Control sequences defined outside of the Blog Builder
+    let custom = parser.control(ctrl);
+
+    // Which arguments are required, and which are optional?
+    let (names, options): (&[&str], &[&str]) = match ctrl {
+        "image" => (&["alt", "src"], &["width", "caption"]),
+        "byline" => (&["author", "date"], &[]),
+        "float" => (&["alt", "src"], &["width", "caption"]),
+        "notice" => if body.is_some() { (&[], &[]) } else { (&["message"], &[]) },
+        "tile" => (&["title", "description", "href", "image"], &["target"]),
+        "mathblock" => (&["type", "title"], &["label"]),
+        "endmath" => (&[], &[]),
+        "qed" => (&[], &[]),
+        "include" => (&["path"], &[]),
+        "toc" => (&[], &["depth"]),
+        "ref" => (&["label"], &[]),
+        _ => if custom.is_some() {
+            (&[], &[])
+        } else {
+            return Expression::Error (ParseError::UnrecognizedControl (ctrl.to_owned()), span);
+        },
+    };
+
+    // Control sequences defined outside of the Blog Builder name their own arguments
+    let names: Vec<String> = if let Some (c) = custom {
+        c.names()
+    } else {
+        names.iter().map(|n| n.to_string()).collect()
+    };
+
+    // Match each value with its argument
+    let args = match bind(ctrl, &names, options, values) {
+        Ok (a) => a,
+        Err (e) => return Expression::Error (e, span),
     };
 
-    // Enforce list length
-    if values.len() != len {
-        return Expression::Error (ParseError::IncorrectArgumentCount {
-            expected: len,
-            actual: values.len(),
-            control: ctrl.to_string(),
-        });
+    // Check the values of optional arguments
+    if let Err (e) = check(ctrl, &args) {
+        return Expression::Error (e, span);
     }
 
+    let values = args.positional.to_owned();
     match ctrl {
         "image" => Expression::FullImage {
             alt: values[0].to_owned(),
             href: values[1].to_owned(),
+            width: args.option("width"),
+            caption: args.option("caption"),
         },
         "byline" => Expression::Byline {
             author: values[0].to_owned(),
@@ -96,26 +255,200 @@ fn build_expr(ctrl: &str, values: Vec<String>) -> Expression {
         "float" => Expression::FloatImage {
             alt: values[0].to_owned(),
             href: values[1].to_owned(),
+            width: args.option("width"),
+            caption: args.option("caption"),
         },
-        "notice" => Expression::Notice (values[0].to_owned()),
+        "notice" => Expression::Notice (body.unwrap_or_else(|| vec![Expression::Text (values[0].to_owned())])),
         "tile" => Expression::Tile {
             title: values[0].to_owned(),
             description: values[1].to_owned(),
             href: values[2].to_owned(),
             image: values[3].to_owned(),
+            target: args.option("target"),
         },
         "mathblock" => Expression::MathBlock {
             blocktype: values[0].to_owned(),
             title: values[1].to_owned(),
+            label: args.option("label"),
+            number: String::new(),
+            body: body.unwrap_or_default(),
+        },
+        "endmath" => Expression::EndMath (span),
+        "qed" => Expression::Qed (span),
+        "include" => Expression::Include {
+            path: values[0].to_owned(),
+            expressions: Vec::new(),
+        },
+        "ref" => Expression::Ref {
+            label: values[0].to_owned(),
+            text: String::new(),
+            href: String::new(),
+            span,
+        },
+        "toc" => Expression::Toc {
+            // Checked above
+            depth: args.option("depth").map_or(DEFAULT_TOC_DEPTH, |d| d.parse().unwrap()),
+            entries: Vec::new(),
         },
-        "endmath" => Expression::EndMath,
-        "qed" => Expression::Qed,
 
         // We already checked above that this is a valid control sequence
-        _ => unreachable!(),
+        _ => Expression::Custom {
+            control: ctrl.to_owned(),
+            html: custom.unwrap().emit(&values),
+            body,
+            inline: false,
+        },
     }
 }
 
+/// Match the values given to a control sequence with its arguments.
+/// 
+/// Values written as `name=value`, where `name` is one of the
+/// arguments, are matched by name, and all other values fill the
+/// required arguments in order, so that a title such as `n=1 case`
+/// is kept as written.
+/// 
+/// # Parameters
+/// - `ctrl` (`&str`): the name of the control sequence
+/// - `names` (`&[String]`): the names of the required arguments
+/// - `options` (`&[&str]`): the names of the optional arguments
+/// - `values` (`Vec<String>`): the values
+/// 
+/// # Returns
+/// A `Result` containing the arguments, or a `ParseError` naming the
+/// missing or unknown argument.
+fn bind(ctrl: &str, names: &[String], options: &[&str], values: Vec<String>) -> Result<Arguments, ParseError> {
+    let mut positional: Vec<Option<String>> = vec![None; names.len()];
+    let mut named = HashMap::new();
+
+    // Number of values given, for error messages
+    let count = values.len();
+
+    for value in values {
+        // Only the names of arguments make a value named
+        let given = split_named(&value)
+            .filter(|(name, _)| names.iter().any(|n| n == name) || options.contains(name));
+
+        if let Some ((name, v)) = given {
+            if let Some (i) = names.iter().position(|n| n == name) {
+                // Required argument, given by name
+                if positional[i].replace(v.to_owned()).is_some() {
+                    return Err (ParseError::DuplicateArgument {
+                        control: ctrl.to_owned(),
+                        argument: name.to_owned(),
+                    });
+                }
+            } else if named.insert(name.to_owned(), v.to_owned()).is_some() {
+                // Optional argument, given twice
+                return Err (ParseError::DuplicateArgument {
+                    control: ctrl.to_owned(),
+                    argument: name.to_owned(),
+                });
+            }
+        } else if let Some (slot) = positional.iter_mut().find(|p| p.is_none()) {
+            // Required argument, given by position
+            *slot = Some (value);
+        } else if let Some ((name, _)) = split_named(&value) {
+            // A value left over that looks named is likely a misspelled argument
+            return Err (ParseError::UnknownArgument {
+                control: ctrl.to_owned(),
+                argument: name.to_owned(),
+                available: names.iter().map(|n| n.to_string()).chain(options.iter().map(|o| o.to_string())).collect(),
+            });
+        } else {
+            return Err (ParseError::IncorrectArgumentCount {
+                expected: names.len(),
+                actual: count,
+                control: ctrl.to_owned(),
+            });
+        }
+    }
+
+    // Make sure that every required argument was given
+    let positional = positional.into_iter()
+        .zip(names)
+        .map(|(p, n)| p.ok_or_else(|| ParseError::MissingArgument {
+            control: ctrl.to_owned(),
+            argument: n.to_owned(),
+        }))
+        .collect::<Result<Vec<String>, ParseError>>()?;
+
+    Ok (Arguments {
+        positional,
+        options: named,
+    })
+}
+
+/// Split a value written as `name=value`.
+/// 
+/// Names are made of lowercase letters, digits, `-` and `_`, so that
+/// text such as `E=mc^2` is not mistaken for a named argument.
+/// 
+/// # Parameters
+/// - `value` (`&str`): the value
+/// 
+/// # Returns
+/// An `Option` containing the name and the value, if named.
+fn split_named(value: &str) -> Option<(&str, &str)> {
+    let (name, v) = value.split_once('=')?;
+
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+
+    if valid {
+        Some ((name, v.trim()))
+    } else {
+        None
+    }
+}
+
+/// Check the values of the optional arguments to a control sequence.
+/// 
+/// # Parameters
+/// - `ctrl` (`&str`): the name of the control sequence
+/// - `args` (`&Arguments`): the arguments
+/// 
+/// # Returns
+/// A `Result` containing a `ParseError` naming any invalid argument.
+fn check(ctrl: &str, args: &Arguments) -> Result<(), ParseError> {
+    let invalid = |argument: &str, value: &str, expected: &str| ParseError::InvalidArgument {
+        control: ctrl.to_owned(),
+        argument: argument.to_owned(),
+        value: value.to_owned(),
+        expected: expected.to_owned(),
+    };
+
+    if let Some (w) = args.options.get("width") {
+        // Check longer units first
+        let number = ["%", "px", "rem", "em", "vw"].iter()
+            .find_map(|u| w.strip_suffix(u))
+            .unwrap_or(w);
+
+        if !number.parse::<f64>().is_ok_and(|n| n >= 0.0) {
+            return Err (invalid("width", w, "widths are written as a number followed by `%`, `px`, `em`, `rem`, or `vw`"));
+        }
+    }
+
+    if let Some (d) = args.options.get("depth") {
+        if !d.parse::<usize>().is_ok_and(|n| (1..=6).contains(&n)) {
+            return Err (invalid("depth", d, "depths are whole numbers from 1 to 6"));
+        }
+    }
+
+    if let Some (l) = args.options.get("label") {
+        if l.is_empty() || !l.chars().all(|c| c.is_alphanumeric() || "-_:.".contains(c)) {
+            return Err (invalid("label", l, "labels are made of letters, digits, `-`, `_`, `:`, and `.`"));
+        }
+    }
+
+    if let Some (t) = args.options.get("target") {
+        if t.is_empty() || !t.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err (invalid("target", t, "targets are `_blank`, `_self`, `_parent`, `_top`, or the name of a window"));
+        }
+    }
+
+    Ok (())
+}
+
 /// Parse a bracketed string into an expression.
 fn parse_square_expr(tokenizer: &mut Tokenizer) -> Expression {
     // Consume opening square bracket
@@ -124,25 +457,26 @@ fn parse_square_expr(tokenizer: &mut Tokenizer) -> Expression {
             // Good!
         } else {
             // Expected closing square, found something else
-            return Expression::Error (ParseError::ExpectedToken (TokenClass::OpenSquare));
+            return Expression::Error (ParseError::ExpectedToken (TokenClass::OpenSquare), tokenizer.span());
         }
     } else {
         // We ran out of tokens :(
-        return Expression::Error (ParseError::UnexpectedEof);
+        return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
     };
 
     // Get value
-    let value = if let Some (opt_t) = tokenizer.expect(TokenClass::Paragraph) {
-        if let Some (t) = opt_t {
-            // Make sure to trim the value
-            t.value.trim().to_owned()
-        } else {
+    let value = match tokenizer.peek() {
+        Some (t) if t.class == TokenClass::CloseSquare || t.class == TokenClass::Newline => {
             // Expected text, found something else
-            return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph));
-        }
-    } else {
+            let _ = tokenizer.next();
+            return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph), tokenizer.span());
+        },
+
+        // Make sure to trim the value
+        Some (_) => raw_text(tokenizer).trim().to_owned(),
+
         // We ran out of tokens :(
-        return Expression::Error (ParseError::UnexpectedEof);
+        None => return Expression::Error (ParseError::UnexpectedEof, tokenizer.span()),
     };
 
     // Consume closing square bracket
@@ -151,12 +485,41 @@ fn parse_square_expr(tokenizer: &mut Tokenizer) -> Expression {
             // Good!
         } else {
             // Expected closing square, found something else
-            return Expression::Error (ParseError::ExpectedToken (TokenClass::CloseSquare));
+            return Expression::Error (ParseError::ExpectedToken (TokenClass::CloseSquare), tokenizer.span());
         }
     } else {
         // We ran out of tokens :(
-        return Expression::Error (ParseError::UnexpectedEof);
+        return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
     };
 
     Expression::Text (value)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::tests::parse;
+
+    #[test]
+    fn menu_inside_block_body_is_not_emitted() {
+        for source in ["::notice\n~\nBody\n::end\n", "::mathblock[Theorem][Title]\n~\nBody\n::end\n"] {
+            let html = parse(source)[0].expression.html(true);
+
+            assert!(html.contains("<p>Body</p>") && !html.contains('~'), "{}", html);
+        }
+    }
+
+    #[test]
+    fn tilde_inside_line_is_text() {
+        let nodes = parse("- ~\n\n| ~ |\n|---|\n");
+
+        assert_eq!(nodes[0].expression.html(true), "<ul><li>~</li></ul>");
+        assert!(nodes[2].expression.html(true).contains("<th>~</th>"));
+    }
+
+    #[test]
+    fn positional_value_may_contain_equals() {
+        let html = parse("::mathblock[Definition][n=1 case]\nBody\n::end\n")[0].expression.html(true);
+
+        assert!(html.contains("<span>Definition. n=1 case</span>"), "{}", html);
+    }
+}
@@ -17,50 +17,76 @@ use crate::{
 pub struct EmphasisParselet { }
 
 impl Parselet for EmphasisParselet {
-    fn parse(&self, _parser: &Parser, tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+    fn parse(&self, parser: &Parser, tokenizer: &mut Tokenizer, token: &Token) -> Expression {
         match token.value.as_str() {
             // Italics
-            "_" | "*" => parse_emphasis(tokenizer, token),
+            "_" | "*" => parse_emphasis(parser, tokenizer, token),
             
             // Bold
-            "__" | "**" => parse_emphasis(tokenizer, token),
+            "__" | "**" => parse_emphasis(parser, tokenizer, token),
 
             // Bold & italics
-            "__*" | "***" | "**_" | "___" => parse_emphasis(tokenizer, token),
+            "__*" | "***" | "**_" | "___" => parse_emphasis(parser, tokenizer, token),
 
             // Not sure what this is...
-            _ => Expression::Error (ParseError::UnrecognizedEmphasis)
+            _ => Expression::Error (ParseError::UnrecognizedEmphasis, token.span)
         }
     }
 }
 
 /// Parse until we see a matching closing delimiter.
-fn parse_emphasis(tokenizer: &mut Tokenizer, token: &Token) -> Expression {
-    // Get the next token (should be raw text)
-    if let Some (t) = tokenizer.next() {
-        if t.class == TokenClass::Paragraph {
-            // Consume the closing delimiter
-            let closing = if let Some (c) = tokenizer.next() {
-                c
-            } else {
-                return Expression::Error (ParseError::UnexpectedEof);
-            };
+/// 
+/// Emphasized text may contain any inline expression, including
+/// emphasized text with a different delimiter, but it may not
+/// continue past the end of the line or the end of a bracketed
+/// expression.
+fn parse_emphasis(parser: &Parser, tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+    // Initialize list of expressions
+    let mut output = Vec::new();
 
-            // Make sure the delimiters are the same
-            if token.value != closing.value {
-                return Expression::Error (ParseError::MismatchedDelimiters);
-            }
-
-            match closing.value.as_str() {
-                "_" | "*" => Expression::Italics (t.value.to_owned()),
-                "__" | "**" => Expression::Bold (t.value.to_owned()),
-                "__*" | "***" | "**_" | "___" => Expression::BoldItalics (t.value.to_owned()),
-                _ => Expression::Error (ParseError::UnrecognizedEmphasis)
-            }
+    loop {
+        let t = if let Some (t) = tokenizer.peek() {
+            t
         } else {
-            Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph))
+            // We ran out of tokens :(
+            return Expression::Error (ParseError::UnclosedEmphasis (token.value.to_owned()), token.span);
+        };
+
+        match t.class {
+            // Is this the closing delimiter?
+            TokenClass::Emphasis if t.value == token.value => {
+                let _ = tokenizer.next();
+                break;
+            },
+
+            // Raw text, including parentheses
+            TokenClass::Paragraph | TokenClass::OpenParen | TokenClass::CloseParen => {
+                let _ = tokenizer.next();
+                output.push(Expression::Text (t.value.to_owned()));
+            },
+
+            // Emphasis can't cross lines, table cells, or brackets
+            TokenClass::Newline | TokenClass::Pipe | TokenClass::CloseSquare => {
+                return Expression::Error (ParseError::UnclosedEmphasis (token.value.to_owned()), token.span);
+            },
+
+            // Parse any other inline expression
+            _ => {
+                let expr = parser.parse_next(tokenizer);
+
+                if let Expression::Error (..) = expr {
+                    return expr;
+                }
+
+                output.push(expr);
+            },
         }
-    } else {
-        Expression::Error (ParseError::UnexpectedEof)
     }
-}
\ No newline at end of file
+
+    match token.value.as_str() {
+        "_" | "*" => Expression::Italics (output),
+        "__" | "**" => Expression::Bold (output),
+        "__*" | "***" | "**_" | "___" => Expression::BoldItalics (output),
+        _ => Expression::Error (ParseError::UnrecognizedEmphasis, token.span)
+    }
+}
@@ -25,22 +25,25 @@ impl Parselet for HeaderParselet {
                 t.value.trim().to_owned()
             } else {
                 // Expected text, found something else
-                return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph));
+                return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph), tokenizer.span());
             }
         } else {
             // We ran out of tokens :(
-            return Expression::Error (ParseError::UnexpectedEof);
+            return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
         };
 
+        // Anchor IDs are assigned once the whole page is parsed
+        let (text, id, permalink) = (value, String::new(), false);
+
         // Check the number of hashes
         match token.value.len() {
-            1 => Expression::H1 (value),
-            2 => Expression::H2 (value),
-            3 => Expression::H3 (value),
-            4 => Expression::H4 (value),
-            5 => Expression::H5 (value),
-            6 => Expression::H6 (value),
-            _ => Expression::Error (ParseError::TooManyHashes),
+            1 => Expression::H1 { text, id, permalink },
+            2 => Expression::H2 { text, id, permalink },
+            3 => Expression::H3 { text, id, permalink },
+            4 => Expression::H4 { text, id, permalink },
+            5 => Expression::H5 { text, id, permalink },
+            6 => Expression::H6 { text, id, permalink },
+            _ => Expression::Error (ParseError::TooManyHashes, token.span),
         }
     }
 }
\ No newline at end of file
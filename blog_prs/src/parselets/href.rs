@@ -1,6 +1,7 @@
 //! Hyperlink parselet.
 
 use blog_tkn::{
+    Span,
     Token,
     TokenClass,
     Tokenizer,
@@ -13,49 +14,93 @@ use crate::{
     ParseError,
 };
 
+use super::paragraph::{
+    parse_bracketed,
+    parse_inline,
+    raw_text,
+};
+
 /// Parselet for hyperlinks.
 pub struct HrefParselet { }
 
 impl Parselet for HrefParselet {
-    fn parse(&self, _parser: &Parser, tokenizer: &mut Tokenizer, _token: &Token) -> Expression {
+    fn parse(&self, parser: &Parser, tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+        // Footnote labels begin with a caret
+        let footnote = matches!(
+            tokenizer.peek(),
+            Some (Token { class: TokenClass::Paragraph, ref value, .. }) if value.starts_with('^')
+        );
+
+        if !footnote && definition(tokenizer, token) {
+            return parse_definition(tokenizer);
+        }
+
+        // Brackets that don't hold a link are just text, as in `[sic]`
+        if !footnote && !link(tokenizer) {
+            return Expression::Text (token.value.to_owned());
+        }
+
         // Get text value
-        let text = if let Some (opt_t) = tokenizer.expect(TokenClass::Paragraph) {
-            if let Some (t) = opt_t {
-                // Make sure to trim the value
-                t.value.trim().to_owned()
-            } else {
-                // Expected text, found something else
-                return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph));
-            }
+        let mut text = Vec::new();
+        let label = if footnote {
+            raw_text(tokenizer)
         } else {
-            // We ran out of tokens :(
-            return Expression::Error (ParseError::UnexpectedEof);
+            parse_bracketed(parser, tokenizer, &mut text);
+            String::new()
         };
 
+        // Report any errors in the text
+        if let Some (e @ Expression::Error (..)) = text.last() {
+            return e.clone();
+        }
+
+        // Make sure to trim the value
+        if let Some (Expression::Text (t)) = text.first_mut() {
+            *t = t.trim_start().to_owned();
+        }
+        if let Some (Expression::Text (t)) = text.last_mut() {
+            *t = t.trim_end().to_owned();
+        }
+
+        if !footnote && text.is_empty() {
+            // Expected text, found something else
+            return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph), tokenizer.span());
+        }
+
         // Consume closing square bracket
         if let Some (opt_t) = tokenizer.eat(TokenClass::CloseSquare) {
             if let Some (()) = opt_t {
                 // Good!
             } else {
                 // Expected closing square, found something else
-                return Expression::Error (ParseError::ExpectedToken (TokenClass::CloseSquare));
+                return Expression::Error (ParseError::ExpectedToken (TokenClass::CloseSquare), tokenizer.span());
             }
         } else {
             // We ran out of tokens :(
-            return Expression::Error (ParseError::UnexpectedEof);
+            return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
         };
 
+        if footnote {
+            let span = token.span.to(tokenizer.span());
+            return parse_footnote(parser, tokenizer, token, label[1..].trim(), span);
+        }
+
+        // Square brackets hold the label of a reference-style link
+        if let Some (Token { class: TokenClass::OpenSquare, .. }) = tokenizer.peek() {
+            return parse_reference(tokenizer, token, text);
+        }
+
         // Consume opening parenthesis
         if let Some (opt_t) = tokenizer.eat(TokenClass::OpenParen) {
             if let Some (()) = opt_t {
                 // Good!
             } else {
                 // Expected closing square, found something else
-                return Expression::Error (ParseError::ExpectedToken (TokenClass::OpenParen));
+                return Expression::Error (ParseError::ExpectedToken (TokenClass::OpenParen), tokenizer.span());
             }
         } else {
             // We ran out of tokens :(
-            return Expression::Error (ParseError::UnexpectedEof);
+            return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
         };
 
         // Get hyperlink value
@@ -65,11 +110,11 @@ impl Parselet for HrefParselet {
                 t.value.trim().to_owned()
             } else {
                 // Expected text, found something else
-                return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph));
+                return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph), tokenizer.span());
             }
         } else {
             // We ran out of tokens :(
-            return Expression::Error (ParseError::UnexpectedEof);
+            return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
         };
 
         // Consume closing parenthesis
@@ -78,11 +123,11 @@ impl Parselet for HrefParselet {
                 // Good!
             } else {
                 // Expected closing square, found something else
-                return Expression::Error (ParseError::ExpectedToken (TokenClass::CloseParen));
+                return Expression::Error (ParseError::ExpectedToken (TokenClass::CloseParen), tokenizer.span());
             }
         } else {
             // We ran out of tokens :(
-            return Expression::Error (ParseError::UnexpectedEof);
+            return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
         };
 
         Expression::Href {
@@ -90,4 +135,242 @@ impl Parselet for HrefParselet {
             href,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Parse a footnote reference or definition, after its label.
+/// 
+/// A label followed by a colon at the start of a line defines
+/// the footnote; anywhere else, it refers to the footnote.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// - `token` (`&Token`): the opening square bracket
+/// - `label` (`&str`): the label of the footnote
+/// - `span` (`Span`): the location of the label, including its
+///   square brackets
+/// 
+/// # Returns
+/// An `Expression` containing the footnote reference or definition.
+fn parse_footnote(parser: &Parser, tokenizer: &mut Tokenizer, token: &Token, label: &str, span: Span) -> Expression {
+    let definition = token.span.column == 1 && matches!(
+        tokenizer.peek(),
+        Some (Token { class: TokenClass::Paragraph, ref value, .. }) if value.starts_with(':')
+    );
+
+    if !definition {
+        return Expression::FootnoteRef {
+            label: label.to_owned(),
+            number: 0,
+            first: false,
+            span,
+        };
+    }
+
+    // Consume the colon, keeping any text after it
+    let mut text = Vec::new();
+    if let Some (t) = tokenizer.next() {
+        if t.value.len() > 1 {
+            text.push(Expression::Text (t.value[1..].to_owned()));
+        }
+    }
+
+    // The text of the footnote is the rest of the line
+    parse_inline(parser, tokenizer, &mut text);
+
+    // Drop the space after the colon
+    if let Some (Expression::Text (t)) = text.first_mut() {
+        *t = t.trim_start().to_owned();
+    }
+
+    Expression::Footnote {
+        label: label.to_owned(),
+        number: 0,
+        text,
+        span,
+    }
+}
+
+/// Check if a hyperlink is a link reference definition.
+/// 
+/// A definition is a label in square brackets at the start of a line,
+/// followed by a colon.
+/// 
+/// # Parameters
+/// - `tokenizer` (`&Tokenizer`): the token stream, after the opening
+///   square bracket
+/// - `token` (`&Token`): the opening square bracket
+/// 
+/// # Returns
+/// A `bool` indicating if the hyperlink is a definition.
+fn definition(tokenizer: &Tokenizer, token: &Token) -> bool {
+    if token.span.column != 1 {
+        return false;
+    }
+
+    // Find the closing square bracket on this line
+    let mut i = 0;
+    while let Some (t) = tokenizer.look_ahead(i) {
+        match t.class {
+            TokenClass::CloseSquare => break,
+            TokenClass::Newline => return false,
+            _ => i += 1,
+        }
+    }
+
+    matches!(
+        tokenizer.look_ahead(i + 1),
+        Some (Token { class: TokenClass::Paragraph, ref value, .. }) if value.starts_with(':')
+    )
+}
+
+/// Check if square brackets hold the text of a hyperlink.
+/// 
+/// The text of a hyperlink is followed by a URI in parentheses or by
+/// the label of a reference-style link in square brackets.
+/// 
+/// # Parameters
+/// - `tokenizer` (`&Tokenizer`): the token stream, after the opening
+///   square bracket
+/// 
+/// # Returns
+/// A `bool` indicating if the brackets hold the text of a hyperlink.
+fn link(tokenizer: &Tokenizer) -> bool {
+    // Find the closing square bracket on this line
+    let mut i = 0;
+    while let Some (t) = tokenizer.look_ahead(i) {
+        match t.class {
+            TokenClass::CloseSquare => break,
+            TokenClass::Newline => return false,
+            _ => i += 1,
+        }
+    }
+
+    matches!(
+        tokenizer.look_ahead(i + 1),
+        Some (Token { class: TokenClass::OpenParen, .. }) | Some (Token { class: TokenClass::OpenSquare, .. })
+    )
+}
+
+/// Parse a link reference definition, after its opening square
+/// bracket.
+/// 
+/// The definition is the rest of the line: a URI, optionally enclosed
+/// in angle brackets, followed by an optional title enclosed in double
+/// quotes, single quotes, or parentheses.
+/// 
+/// # Parameters
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// 
+/// # Returns
+/// An `Expression` containing the definition.
+fn parse_definition(tokenizer: &mut Tokenizer) -> Expression {
+    let label = raw_text(tokenizer).trim().to_owned();
+
+    // Consume the closing square bracket
+    let _ = tokenizer.next();
+
+    // Take the rest of the line as written, dropping the colon
+    let mut line = String::new();
+    while let Some (t) = tokenizer.next() {
+        match t.class {
+            TokenClass::Newline => break,
+
+            // Restore the delimiters of inline code
+            TokenClass::Backtick => line.push_str(&format!("`{}`", t.value)),
+
+            _ => line.push_str(&t.value),
+        }
+    }
+    let line = line[1..].trim();
+
+    // Split the URI from the title
+    let (href, rest) = if let Some (l) = line.strip_prefix('<') {
+        match l.split_once('>') {
+            Some ((h, r)) => (h, r),
+            None => (line, ""),
+        }
+    } else {
+        line.split_once(char::is_whitespace).unwrap_or((line, ""))
+    };
+
+    // Titles are enclosed in quotes or parentheses
+    let rest = rest.trim();
+    let title = match (rest.chars().next(), rest.chars().last()) {
+        (None, _) => "",
+        (Some ('"'), Some ('"'))
+            | (Some ('\''), Some ('\''))
+            | (Some ('('), Some (')')) if rest.len() > 1 => &rest[1..rest.len() - 1],
+        _ => return Expression::Error (ParseError::InvalidLinkDefinition (label), tokenizer.span()),
+    };
+
+    if href.is_empty() {
+        // Expected a URI, found nothing
+        return Expression::Error (ParseError::ExpectedToken (TokenClass::Paragraph), tokenizer.span());
+    }
+
+    Expression::LinkDef {
+        label,
+        href: href.to_owned(),
+        title: title.to_owned(),
+    }
+}
+
+/// Parse the label of a reference-style link, after the text of the
+/// link.
+/// 
+/// An empty label, as in `[text][]`, uses the text of the link as
+/// its label.
+/// 
+/// # Parameters
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// - `token` (`&Token`): the opening square bracket of the text
+/// - `text` (`Vec<Expression>`): the text of the link
+/// 
+/// # Returns
+/// An `Expression` containing the reference-style link, which is
+/// resolved once the whole page has been parsed.
+fn parse_reference(tokenizer: &mut Tokenizer, token: &Token, text: Vec<Expression>) -> Expression {
+    // Consume opening square bracket
+    let _ = tokenizer.next();
+
+    let label = raw_text(tokenizer);
+
+    // Consume closing square bracket
+    if let Some (opt_t) = tokenizer.eat(TokenClass::CloseSquare) {
+        if opt_t.is_none() {
+            // Expected closing square, found something else
+            return Expression::Error (ParseError::ExpectedToken (TokenClass::CloseSquare), tokenizer.span());
+        }
+    } else {
+        // We ran out of tokens :(
+        return Expression::Error (ParseError::UnexpectedEof, tokenizer.span());
+    };
+
+    let label = if label.trim().is_empty() {
+        text.iter().map(|e| e.to_string()).collect()
+    } else {
+        label.trim().to_owned()
+    };
+
+    Expression::LinkRef {
+        text,
+        label,
+        href: String::new(),
+        title: String::new(),
+        span: token.span.to(tokenizer.span()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::tests::parse;
+
+    #[test]
+    fn brackets_without_link_are_text() {
+        let nodes = parse("[sic] and \\[1] here\n\na \\] there\n");
+
+        assert_eq!(nodes[0].expression.html(true), "<p>[sic] and [1] here</p>");
+        assert_eq!(nodes[2].expression.html(true), "<p>a ] there</p>");
+    }
+}
@@ -0,0 +1,21 @@
+//! Raw HTML block parselet.
+
+use blog_tkn::{
+    Token,
+    Tokenizer,
+};
+
+use crate::{
+    Parser,
+    Parselet,
+    Expression,
+};
+
+/// Parselet for raw HTML blocks.
+pub struct RawHtmlParselet { }
+
+impl Parselet for RawHtmlParselet {
+    fn parse(&self, _parser: &Parser, _tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+        Expression::RawHtml (token.value.to_owned())
+    }
+}
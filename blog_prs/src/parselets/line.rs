@@ -0,0 +1,83 @@
+//! Line handling for block parselets.
+//! 
+//! Blocks such as lists and tables continue over several lines.  Inside
+//! of a blockquote, each of these lines begins with a blockquote marker,
+//! which must be skipped to find the first token of the line.
+
+use blog_tkn::{
+    Token,
+    TokenClass,
+    Tokenizer,
+};
+
+use crate::Parser;
+
+/// Count the tokens taken by the blockquote marker at the start of
+/// the next line.
+/// 
+/// Returns `None` if the next line does not belong to the blockquote
+/// currently being parsed.
+fn quote_offset(parser: &Parser, tokenizer: &Tokenizer) -> Option<usize> {
+    let depth = parser.quote_depth();
+
+    if depth == 0 {
+        return Some (0);
+    }
+
+    let quote = tokenizer.peek()?;
+    if quote.class == TokenClass::Quote && quote.value.len() == depth {
+        Some (1)
+    } else {
+        None
+    }
+}
+
+/// Look ahead `tokens` tokens on the next line, skipping any blockquote
+/// marker.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&Tokenizer`): the token stream
+/// - `tokens` (`usize`): the number of tokens to look ahead
+///   (zero is the first token on the line)
+/// 
+/// # Returns
+/// An `Option<Token>` containing the token, if the next line belongs
+/// to the current block and the token is available.
+pub fn look_ahead_line(parser: &Parser, tokenizer: &Tokenizer, tokens: usize) -> Option<Token> {
+    let offset = quote_offset(parser, tokenizer)?;
+
+    tokenizer.look_ahead(offset + tokens)
+}
+
+/// Peek at the first token on the next line, if it is of a given class.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&Tokenizer`): the token stream
+/// - `class` (`TokenClass`): the expected class of the token
+/// 
+/// # Returns
+/// An `Option<Token>` containing the token, if it is of the expected
+/// class and the next line belongs to the current block.
+pub fn peek_line(parser: &Parser, tokenizer: &Tokenizer, class: TokenClass) -> Option<Token> {
+    look_ahead_line(parser, tokenizer, 0).filter(|t| t.class == class)
+}
+
+/// Consume the first token on the next line, along with any blockquote
+/// marker before it.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// 
+/// # Returns
+/// None.
+pub fn consume_line(parser: &Parser, tokenizer: &mut Tokenizer) {
+    // Consume the blockquote marker, if we're in a blockquote
+    if parser.quote_depth() > 0 {
+        let _ = tokenizer.next();
+    }
+
+    let _ = tokenizer.next();
+}
@@ -0,0 +1,154 @@
+//! List parselet.
+
+use blog_tkn::{
+    Token,
+    TokenClass,
+    Tokenizer,
+};
+
+use crate::{
+    Parser,
+    Parselet,
+    Expression,
+};
+
+use super::{
+    line::{
+        consume_line,
+        peek_line,
+    },
+    paragraph::parse_inline,
+};
+
+/// Parselet for ordered and unordered lists.
+pub struct ListParselet { }
+
+impl Parselet for ListParselet {
+    fn parse(&self, parser: &Parser, tokenizer: &mut Tokenizer, token: &Token) -> Expression {
+        parse_list(parser, tokenizer, token, None)
+    }
+}
+
+/// Parse a list, starting from the marker of its first item.
+/// 
+/// Items indented further than the first item are parsed as nested
+/// lists.  The list ends at the first line that is not a list item,
+/// at an item that is indented no further than the items of the
+/// enclosing list (or, at the top level, less than the first item),
+/// or at an item of a different kind of list.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// - `first` (`&Token`): the marker of the first item, which has
+///   already been consumed
+/// - `parent` (`Option<usize>`): the indentation of the enclosing
+///   list, if this list is nested
+/// 
+/// # Returns
+/// An `Expression` containing the list.
+fn parse_list(parser: &Parser, tokenizer: &mut Tokenizer, first: &Token, parent: Option<usize>) -> Expression {
+    let indent = indentation(&first.value);
+    let ordered = is_ordered(&first.value);
+
+    // Items indented less than the first item still belong to a
+    //  nested list, as long as they are indented further than its parent
+    let least = parent.map_or(indent, |p| p + 1);
+
+    // Initialize list of items
+    let mut items = Vec::new();
+
+    // Parse the first item
+    let mut item = Vec::new();
+    parse_inline(parser, tokenizer, &mut item);
+
+    while let Some (t) = peek_line(parser, tokenizer, TokenClass::ListItem) {
+        let i = indentation(&t.value);
+
+        if i > indent {
+            // Consume the marker and parse a nested list
+            consume_line(parser, tokenizer);
+            item.push(parse_list(parser, tokenizer, &t, Some (indent)));
+        } else if i >= least && is_ordered(&t.value) == ordered {
+            // Consume the marker and parse the next item
+            consume_line(parser, tokenizer);
+            items.push(Expression::ListItem (item));
+            item = Vec::new();
+            parse_inline(parser, tokenizer, &mut item);
+        } else {
+            // This item belongs to another list
+            break;
+        }
+    }
+
+    items.push(Expression::ListItem (item));
+
+    if ordered {
+        Expression::OrderedList {
+            start: number(&first.value),
+            items,
+        }
+    } else {
+        Expression::UnorderedList (items)
+    }
+}
+
+/// Measure the indentation of a list item marker.
+/// 
+/// Tabs count as four spaces.
+/// 
+/// # Parameters
+/// - `marker` (`&str`): the marker, including its indentation
+/// 
+/// # Returns
+/// A `usize` containing the indentation, in spaces.
+fn indentation(marker: &str) -> usize {
+    marker.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Check if a list item marker belongs to an ordered list.
+/// 
+/// # Parameters
+/// - `marker` (`&str`): the marker, including its indentation
+/// 
+/// # Returns
+/// A `bool` indicating if the marker begins with a number.
+fn is_ordered(marker: &str) -> bool {
+    marker.trim_start().starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Get the number of an ordered list item marker.
+/// 
+/// # Parameters
+/// - `marker` (`&str`): the marker, including its indentation
+/// 
+/// # Returns
+/// A `usize` containing the number, or one if it is too large.
+fn number(marker: &str) -> usize {
+    marker.trim()
+        .trim_end_matches(['.', ')'])
+        .parse()
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::tests::parse;
+
+    #[test]
+    fn ordered_list_keeps_start() {
+        let nodes = parse("3. three\n4. four\n");
+
+        assert_eq!(nodes[0].expression.html(true), "<ol start=\"3\"><li>three</li><li>four</li></ol>");
+    }
+
+    #[test]
+    fn uneven_indentation_nests_once() {
+        let nodes = parse("- a\n   - b\n  - c\n- d\n");
+
+        assert_eq!(nodes[0].expression.html(true), "<ul><li>a<ul><li>b</li><li>c</li></ul></li><li>d</li></ul>");
+    }
+}
@@ -1,17 +1,38 @@
 //! Parselets for the Blog Builder parser.
+//! 
+//! These are available so that extensions may fall back on them
+//! or reuse their helper functions.
 
+mod blockquote;
+mod code;
+mod codeblock;
 mod control;
 mod emphasis;
 mod header;
 mod href;
+mod html;
+mod line;
+mod list;
 mod paragraph;
+mod table;
 mod menu;
 mod newline;
 
+pub use blockquote::BlockquoteParselet;
+pub use code::CodeParselet;
+pub use codeblock::CodeBlockParselet;
 pub use control::ControlParselet;
 pub use emphasis::EmphasisParselet;
 pub use header::HeaderParselet;
 pub use href::HrefParselet;
-pub use paragraph::ParagraphParselet;
+pub use html::RawHtmlParselet;
+pub use list::ListParselet;
+pub use paragraph::{
+    ParagraphParselet,
+    parse_bracketed,
+    parse_inline,
+    raw_text,
+};
+pub use table::TableParselet;
 pub use menu::MenuParselet;
 pub use newline::NewlineParselet;
\ No newline at end of file
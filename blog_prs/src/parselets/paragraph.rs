@@ -10,6 +10,7 @@ use crate::{
     Parser,
     Parselet,
     Expression,
+    ParseError,
 };
 
 /// Parselet for paragraphs.
@@ -21,46 +22,190 @@ impl Parselet for ParagraphParselet {
         let mut output = Vec::new();
 
         // Push the first token, since it's already been consumed
-        output.push(Expression::Text (token.value.to_owned()));
+        push_text(tokenizer, &mut output, &token.value);
 
-        // Parse until we see a newline
-        while let Some (t) = tokenizer.peek() {
-            // Is this a newline?
-            if t.class == TokenClass::Newline {
-                // Consume the newline
-                let _ = tokenizer.next();
+        // Parse the rest of the line
+        parse_inline(parser, tokenizer, &mut output);
+
+        Expression::Paragraph (output)
+    }
+}
+
+/// Parse inline expressions until the end of the line.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// - `output` (`&mut Vec<Expression>`): the list of expressions
+///   to which the inline expressions are added
+/// 
+/// # Returns
+/// None.
+pub fn parse_inline(parser: &Parser, tokenizer: &mut Tokenizer, output: &mut Vec<Expression>) {
+    // Parse until we see a newline
+    while let Some (t) = tokenizer.peek() {
+        // Pipes end the cells of a table row
+        if t.class == TokenClass::Pipe {
+            return;
+        }
+
+        // Is this a newline?
+        if t.class == TokenClass::Newline {
+            // Consume the newline
+            let _ = tokenizer.next();
+
+            // Return
+            return;
+        }
 
-                // Return
-                return Expression::Paragraph (output);
+        // Parse raw text or another expression
+        // Include parentheses, square brackets that don't close
+        //  a link, and tildes that don't begin a line
+        if t.class == TokenClass::Paragraph
+            || t.class == TokenClass::OpenParen
+            || t.class == TokenClass::CloseParen
+            || t.class == TokenClass::CloseSquare
+            || t.class == TokenClass::Menu
+        {
+            // Consume the text
+            let _ = tokenizer.next();
+
+            // Push the text to the paragraph
+            push_text(tokenizer, output, &t.value);
+        } else {
+            // Get the next expression out of the token stream
+            let expr = parser.parse_next(tokenizer);
+
+            // Keep any errors in place and resume parsing after them,
+            //  so that all errors in this paragraph are reported
+            let error = matches!(expr, Expression::Error (..));
+
+            output.push(expr);
+
+            // Stop if the erroneous expression consumed the newline
+            if error && tokenizer.at_line_start() {
+                return;
             }
+        }
+    }
+
+    // We never found the newline, but that's ok, we're at EOF
+}
 
-            // Parse raw text or another expression
-            // Include parentheses
-            if t.class == TokenClass::Paragraph
-                || t.class == TokenClass::OpenParen
-                || t.class == TokenClass::CloseParen
-            {
-                // Consume the text
+/// Parse inline expressions until the end of a bracketed expression.
+/// 
+/// The closing square bracket is not consumed.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// - `output` (`&mut Vec<Expression>`): the list of expressions
+///   to which the inline expressions are added
+/// 
+/// # Returns
+/// None.
+pub fn parse_bracketed(parser: &Parser, tokenizer: &mut Tokenizer, output: &mut Vec<Expression>) {
+    while let Some (t) = tokenizer.peek() {
+        match t.class {
+            // Stop at the closing bracket, or at a blank line if
+            //  the bracket is never closed
+            TokenClass::CloseSquare | TokenClass::Newline => return,
+
+            // Raw text, including parentheses and tildes
+            TokenClass::Paragraph | TokenClass::OpenParen | TokenClass::CloseParen | TokenClass::Menu => {
                 let _ = tokenizer.next();
+                push_text(tokenizer, output, &t.value);
+            },
 
-                // Push the text to the paragraph
-                output.push(Expression::Text (t.value.to_owned()));
-            } else {
-                // Get the next expression out of the token stream
+            // Parse any other inline expression
+            _ => {
                 let expr = parser.parse_next(tokenizer);
 
-                // Return any errors, if found
-                // 
-                // All errors must occur at the top level
-                if let Expression::Error (_) = expr {
-                    return expr;
-                }
+                // Stop at the first error
+                let error = matches!(expr, Expression::Error (..));
 
                 output.push(expr);
-            }
+
+                if error {
+                    return;
+                }
+            },
         }
+    }
+}
 
-        // We never found the newline, but that's ok, we're at EOF
-        Expression::Paragraph (output)
+/// Add text to a list of inline expressions.
+/// 
+/// Control sequences are only recognized at the start of a line, so
+/// text ending in `::ref` that is followed by a bracketed label is
+/// parsed here as a cross-reference.
+/// 
+/// # Parameters
+/// - `tokenizer` (`&mut Tokenizer`): the token stream, just after
+///   the text
+/// - `output` (`&mut Vec<Expression>`): the list of expressions
+///   to which the text is added
+/// - `text` (`&str`): the text
+/// 
+/// # Returns
+/// None.
+fn push_text(tokenizer: &mut Tokenizer, output: &mut Vec<Expression>, text: &str) {
+    let before = match text.strip_suffix("::ref") {
+        Some (b) if tokenizer.peek().is_some_and(|t| t.class == TokenClass::OpenSquare) => b,
+        _ => {
+            output.push(Expression::Text (text.to_owned()));
+            return;
+        },
+    };
+
+    if !before.is_empty() {
+        output.push(Expression::Text (before.to_owned()));
     }
-}
\ No newline at end of file
+
+    // Consume the opening bracket
+    let _ = tokenizer.next();
+    let open = tokenizer.span();
+    let label = raw_text(tokenizer);
+
+    // Consume the closing bracket
+    let expr = match tokenizer.eat(TokenClass::CloseSquare) {
+        Some (Some (())) => Expression::Ref {
+            label: label.trim().to_owned(),
+            text: String::new(),
+            href: String::new(),
+            span: open.to(tokenizer.span()),
+        },
+        Some (None) => Expression::Error (ParseError::ExpectedToken (TokenClass::CloseSquare), tokenizer.span()),
+        None => Expression::Error (ParseError::UnexpectedEof, tokenizer.span()),
+    };
+
+    output.push(expr);
+}
+
+/// Read the raw text of a bracketed expression.
+/// 
+/// The closing square bracket is not consumed.
+/// 
+/// # Parameters
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// 
+/// # Returns
+/// A `String` containing the text, exactly as written.
+pub fn raw_text(tokenizer: &mut Tokenizer) -> String {
+    let mut text = String::new();
+
+    while let Some (t) = tokenizer.peek() {
+        match t.class {
+            TokenClass::CloseSquare | TokenClass::Newline => break,
+
+            // Restore the delimiters of inline code
+            TokenClass::Backtick => text.push_str(&format!("`{}`", t.value)),
+
+            _ => text.push_str(&t.value),
+        }
+
+        let _ = tokenizer.next();
+    }
+
+    text
+}
@@ -0,0 +1,221 @@
+//! Table parselet.
+
+use blog_tkn::{
+    Token,
+    TokenClass,
+    Tokenizer,
+};
+
+use crate::{
+    Alignment,
+    Parser,
+    Parselet,
+    Expression,
+};
+
+use super::{
+    line::{
+        consume_line,
+        look_ahead_line,
+        peek_line,
+    },
+    paragraph::parse_inline,
+};
+
+/// Parselet for pipe tables.
+pub struct TableParselet { }
+
+impl Parselet for TableParselet {
+    fn parse(&self, parser: &Parser, tokenizer: &mut Tokenizer, _token: &Token) -> Expression {
+        // Parse the header row
+        let header = parse_row(parser, tokenizer);
+
+        // The header row must be followed by a delimiter row
+        let alignments = if let Some ((alignments, length)) = delimiter_row(parser, tokenizer) {
+            // Consume the delimiter row
+            consume_line(parser, tokenizer);
+            for _ in 1..length {
+                let _ = tokenizer.next();
+            }
+
+            alignments
+        } else {
+            // This isn't a table, so treat the row as a paragraph
+            let mut output = Vec::new();
+
+            for cell in header {
+                output.push(Expression::Text ("|".to_string()));
+                output.extend(cell);
+            }
+
+            return Expression::Paragraph (output);
+        };
+
+        // Parse each body row
+        let mut rows = Vec::new();
+        while peek_line(parser, tokenizer, TokenClass::Pipe).is_some() {
+            consume_line(parser, tokenizer);
+            rows.push(parse_row(parser, tokenizer));
+        }
+
+        // Every row has as many cells as the header
+        let columns = header.len();
+        let rows = rows.into_iter()
+            .map(|mut row| {
+                row.resize(columns, Vec::new());
+                row.into_iter().map(cell).collect()
+            })
+            .collect();
+
+        Expression::Table {
+            alignments: alignments.into_iter().chain(std::iter::repeat(None)).take(columns).collect(),
+            header: header.into_iter().map(cell).collect(),
+            rows,
+        }
+    }
+}
+
+/// Parse the cells of a table row, after its leading pipe.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&mut Tokenizer`): the token stream
+/// 
+/// # Returns
+/// A `Vec<Vec<Expression>>` containing the contents of each cell.
+fn parse_row(parser: &Parser, tokenizer: &mut Tokenizer) -> Vec<Vec<Expression>> {
+    // Initialize list of cells
+    let mut cells = Vec::new();
+
+    loop {
+        // Parse the cell up to the next pipe or the end of the line
+        let mut cell = Vec::new();
+        parse_inline(parser, tokenizer, &mut cell);
+
+        // Stop if the cell ended at the end of the line
+        if !tokenizer.at_line_start() && tokenizer.peek().map(|t| t.class) == Some (TokenClass::Pipe) {
+            // Consume the pipe
+            let _ = tokenizer.next();
+            cells.push(cell);
+
+            // A trailing pipe ends the row
+            match tokenizer.peek().map(|t| t.class) {
+                Some (TokenClass::Newline) => {
+                    let _ = tokenizer.next();
+                    break;
+                },
+                None => break,
+                _ => (),
+            }
+        } else {
+            // The row ended without a trailing pipe
+            if !cell.is_empty() {
+                cells.push(cell);
+            }
+
+            break;
+        }
+    }
+
+    cells
+}
+
+/// Check if the next line is a delimiter row.
+/// 
+/// # Parameters
+/// - `parser` (`&Parser`): the original parser
+/// - `tokenizer` (`&Tokenizer`): the token stream
+/// 
+/// # Returns
+/// An `Option` containing the alignment of each column and the number
+/// of tokens in the row (after any blockquote marker), if the next
+/// line is a delimiter row.
+fn delimiter_row(parser: &Parser, tokenizer: &Tokenizer) -> Option<(Vec<Option<Alignment>>, usize)> {
+    // The row must begin with a pipe
+    look_ahead_line(parser, tokenizer, 0).filter(|t| t.class == TokenClass::Pipe)?;
+
+    let mut alignments = Vec::new();
+
+    // Text of the current cell
+    let mut text = String::new();
+
+    let mut i = 1;
+    while let Some (t) = look_ahead_line(parser, tokenizer, i) {
+        i += 1;
+
+        match t.class {
+            TokenClass::Paragraph => text.push_str(&t.value),
+            TokenClass::Pipe | TokenClass::Newline => {
+                if !text.trim().is_empty() {
+                    alignments.push(alignment(text.trim())?);
+                }
+
+                text.clear();
+
+                if t.class == TokenClass::Newline {
+                    break;
+                }
+            },
+            _ => return None,
+        }
+    }
+
+    // Handle a final cell without a trailing pipe at EOF
+    if !text.trim().is_empty() {
+        alignments.push(alignment(text.trim())?);
+    }
+
+    if alignments.is_empty() {
+        None
+    } else {
+        Some ((alignments, i))
+    }
+}
+
+/// Get the alignment indicated by a cell of a delimiter row.
+/// 
+/// # Parameters
+/// - `delimiter` (`&str`): the trimmed text of the cell
+/// 
+/// # Returns
+/// An `Option` containing the alignment, which is `None` if the cell
+/// does not indicate one, or `None` if the cell is not a valid
+/// delimiter.
+fn alignment(delimiter: &str) -> Option<Option<Alignment>> {
+    let left = delimiter.starts_with(':');
+    let right = delimiter.ends_with(':');
+
+    // The rest of the delimiter must be hyphens
+    let hyphens = delimiter.trim_matches(':');
+    if hyphens.is_empty() || hyphens.chars().any(|c| c != '-') {
+        return None;
+    }
+
+    let alignment = match (left, right) {
+        (true, true) => Some (Alignment::Center),
+        (true, false) => Some (Alignment::Left),
+        (false, true) => Some (Alignment::Right),
+        (false, false) => None,
+    };
+
+    Some (alignment)
+}
+
+/// Construct a table cell, trimming the whitespace around its contents.
+/// 
+/// # Parameters
+/// - `contents` (`Vec<Expression>`): the contents of the cell
+/// 
+/// # Returns
+/// An `Expression` containing the cell.
+fn cell(mut contents: Vec<Expression>) -> Expression {
+    if let Some (Expression::Text (t)) = contents.first_mut() {
+        *t = t.trim_start().to_string();
+    }
+
+    if let Some (Expression::Text (t)) = contents.last_mut() {
+        *t = t.trim_end().to_string();
+    }
+
+    Expression::Paragraph (contents)
+}
@@ -1,6 +1,9 @@
 //! Parser for the Blog Builder.
 
-use std::collections::HashMap;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+};
 
 use blog_tkn::{
     Tokenizer,
@@ -8,17 +11,29 @@ use blog_tkn::{
 };
 
 use crate::{
+    Control,
     Expression,
+    Inline,
+    Node,
+    footnotes,
+    inline,
     Parselet,
     ParseError,
     parselets::{
+        BlockquoteParselet,
+        CodeParselet,
+        CodeBlockParselet,
         ControlParselet,
         EmphasisParselet,
         HeaderParselet,
         HrefParselet,
+        ListParselet,
         ParagraphParselet,
+        RawHtmlParselet,
+        TableParselet,
         MenuParselet,
         NewlineParselet,
+        parse_inline,
     },
 };
 
@@ -27,6 +42,15 @@ use crate::{
 pub struct Parser {
     /// Parselets.
     parselets: HashMap<TokenClass, Box<dyn Parselet>>,
+
+    /// Control sequences defined outside of the Blog Builder.
+    controls: HashMap<String, Box<dyn Control>>,
+
+    /// Inline syntax defined outside of the Blog Builder.
+    inlines: Vec<Box<dyn Inline>>,
+
+    /// Nesting depth of the blockquote currently being parsed.
+    quote_depth: Cell<usize>,
 }
 
 impl Parser {
@@ -34,8 +58,7 @@ impl Parser {
     /// Construct a new parser from a token stream.
     /// 
     /// # Parameters
-    /// None.mod image;
-
+    /// None.
     /// 
     /// # Returns
     /// A new `Parser`.
@@ -51,33 +74,161 @@ impl Parser {
         parselets.insert(OpenSquare, Box::new(HrefParselet { }));
         parselets.insert(Emphasis, Box::new(EmphasisParselet { }));
         parselets.insert(Control, Box::new(ControlParselet { }));
+        parselets.insert(CodeBlock, Box::new(CodeBlockParselet { }));
+        parselets.insert(Backtick, Box::new(CodeParselet { }));
+        parselets.insert(ListItem, Box::new(ListParselet { }));
+        parselets.insert(Quote, Box::new(BlockquoteParselet { }));
+        parselets.insert(Pipe, Box::new(TableParselet { }));
+        parselets.insert(RawHtml, Box::new(RawHtmlParselet { }));
 
         Self {
             parselets,
+            controls: HashMap::new(),
+            inlines: Vec::new(),
+            quote_depth: Cell::new(0),
         }
     }
 
+    /// Register a parselet for a class of token, replacing the
+    /// current one.
+    /// 
+    /// The replaced parselet is returned so that the new parselet
+    /// may fall back on it.
+    /// 
+    /// # Parameters
+    /// - `class` (`TokenClass`): the class of token
+    /// - `parselet` (`impl Parselet`): the parselet
+    /// 
+    /// # Returns
+    /// An `Option<Box<dyn Parselet>>` containing the replaced parselet.
+    pub fn register(&mut self, class: TokenClass, parselet: impl Parselet + 'static) -> Option<Box<dyn Parselet>> {
+        self.parselets.insert(class, Box::new(parselet))
+    }
+
+    /// Define a new control sequence.
+    /// 
+    /// Control sequences built into the Blog Builder cannot be
+    /// redefined.
+    /// 
+    /// # Parameters
+    /// - `name` (`&str`): the name of the control sequence
+    /// - `control` (`impl Control`): the control sequence
+    /// 
+    /// # Returns
+    /// None.
+    pub fn define(&mut self, name: &str, control: impl Control + 'static) {
+        self.controls.insert(name.to_owned(), Box::new(control));
+    }
+
+    /// Define new inline syntax.
+    /// 
+    /// Inline syntax is applied to text after parsing, so it is not
+    /// applied to code or to the arguments of control sequences.
+    /// 
+    /// # Parameters
+    /// - `syntax` (`impl Inline`): the inline syntax
+    /// 
+    /// # Returns
+    /// None.
+    pub fn inline(&mut self, syntax: impl Inline + 'static) {
+        self.inlines.push(Box::new(syntax));
+    }
+
+    /// Get a control sequence defined outside of the Blog Builder.
+    /// 
+    /// # Parameters
+    /// - `name` (`&str`): the name of the control sequence
+    /// 
+    /// # Returns
+    /// An `Option<&dyn Control>` containing the control sequence,
+    /// if it has been defined.
+    pub(crate) fn control(&self, name: &str) -> Option<&dyn Control> {
+        self.controls.get(name).map(|c| c.as_ref())
+    }
+
+    /// Get the nesting depth of the blockquote currently being parsed.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `usize` containing the depth (zero outside of blockquotes).
+    pub(crate) fn quote_depth(&self) -> usize {
+        self.quote_depth.get()
+    }
+
+    /// Set the nesting depth of the blockquote currently being parsed.
+    /// 
+    /// # Parameters
+    /// - `depth` (`usize`): the new depth
+    /// 
+    /// # Returns
+    /// The previous depth.
+    pub(crate) fn set_quote_depth(&self, depth: usize) -> usize {
+        self.quote_depth.replace(depth)
+    }
+
     /// Parse a list of expressions.
     /// 
     /// # Parameters
     /// - `tokenizer` (`&mut Tokenizer`): a reference to the input token stream
     /// 
     /// # Returns
-    /// A `Vec<Expression>` containing the list of expressions.
-    pub fn parse(&self, tokenizer: &mut Tokenizer) -> Vec<Expression> {
+    /// A `Vec<Node>` containing the list of expressions and their
+    /// locations in the source, with footnotes numbered.
+    pub fn parse(&self, tokenizer: &mut Tokenizer) -> Vec<Node> {
         // Initialize output list of expressions
         let mut output = Vec::new();
 
-        while tokenizer.peek().is_some() {
+        while let Some (t) = tokenizer.peek() {
             // Get the next expression from the token stream
-            let expression = self.parse_next(tokenizer);
+            let expression = self.parse_block(tokenizer);
+
+            // Skip the rest of any erroneous expression so that it
+            //  doesn't cause follow-on errors
+            if let Expression::Error (..) = expression {
+                tokenizer.synchronize();
+            }
 
-            output.push(expression);
+            output.push(Node {
+                expression,
+                span: t.span,
+            });
         }
 
+        // Number footnotes now that all references are known
+        footnotes::number(&mut output);
+
+        // Apply inline syntax defined outside of the Blog Builder
+        inline::apply(&self.inlines, &mut output);
+
         output
     }
 
+    /// Parse the next block expression out of the tokenizer.
+    /// 
+    /// If the line begins with an inline expression, the rest of the
+    /// line is parsed with it as a paragraph.
+    /// 
+    /// # Parameters
+    /// - `tokenizer` (`&mut Tokenizer`): a reference to the input token stream
+    /// 
+    /// # Returns
+    /// An `Expression` containing the next block expression in the stream.
+    pub fn parse_block(&self, tokenizer: &mut Tokenizer) -> Expression {
+        let expression = self.parse_next(tokenizer);
+
+        if !expression.is_inline() {
+            return expression;
+        }
+
+        // Parse the rest of the line
+        let mut output = vec![expression];
+        parse_inline(self, tokenizer, &mut output);
+
+        Expression::Paragraph (output)
+    }
+
     /// Parse the next expression out of the tokenizer.
     /// 
     /// # Parameters
@@ -99,10 +250,42 @@ impl Parser {
             p
         } else {
             // No parselet available
-            return Expression::Error (ParseError::NoParselet (token.class));
+            return Expression::Error (ParseError::NoParselet (token.class), token.span);
         };
 
         // Parse as far as possible and return
         parselet.parse(self, tokenizer, &token)
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use blog_tkn::Tokenizer;
+
+    use crate::{
+        Expression,
+        Node,
+        Parser,
+    };
+
+    /// Parse a source string with a default parser.
+    pub(crate) fn parse(source: &str) -> Vec<Node> {
+        Parser::new().parse(&mut Tokenizer::from(source.to_owned()))
+    }
+
+    #[test]
+    fn unclosed_paren_ends_at_blank_line() {
+        let nodes = parse("[broken link](\n\n::mathblock[Theorem][Foo]\nBody\n::end\n");
+
+        // Only the broken link is an error
+        let errors = nodes.iter()
+            .filter(|n| matches!(n.expression, Expression::Error (..)))
+            .count();
+        assert_eq!(errors, 1);
+
+        assert!(nodes.iter().any(|n| matches!(
+            &n.expression,
+            Expression::MathBlock { title, .. } if title == "Foo"
+        )));
+    }
+}
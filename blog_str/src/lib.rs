@@ -8,4 +8,7 @@
 
 mod sitetree;
 
-pub use sitetree::SiteTree;
\ No newline at end of file
+pub use sitetree::{
+    SiteTree,
+    pages,
+};
\ No newline at end of file
@@ -72,7 +72,14 @@ pub struct SiteTree {
     config: Config,
 
     /// List of all file stems relative to the source directory.
+    /// 
+    /// Files inside of a directory whose name begins with `_`, or
+    /// whose own name begins with `_`, are not built as pages.
     files: Vec<PathBuf>,
+
+    /// List of file stems that are not built as pages, because a
+    /// component of their path begins with `_`.
+    hidden: Vec<PathBuf>,
 }
 
 impl SiteTree {
@@ -99,20 +106,12 @@ impl SiteTree {
         // Construct the output directory
         let output_directory: PathBuf = root.join(OUTPUT_DIR_NAME);
 
-        // Construct a source file check closure
-        let is_source_file = |f: &DirEntry| f.path()
-            .extension()
-            .map(|osstr| osstr.to_str()) == Some (Some (SOURCE_FILE_EXT));
-
         // Walk through the source directory
-        let files = WalkDir::new(&source_directory)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(is_source_file)
-            .map(|f| f.path().to_owned())
-            .filter_map(|p| pathdiff::diff_paths(p, &source_directory))
+        let files = pages(&source_directory);
+        let hidden = sources(&source_directory).into_iter()
+            .filter(|p| is_hidden(p))
             .map(|f| f.with_extension(""))
-            .collect::<Vec<PathBuf>>();
+            .collect();
 
         // Get configuration information
         let config = unwrap_or_return!(Config::get(&root));
@@ -122,6 +121,7 @@ impl SiteTree {
             source_directory,
             output_directory,
             files,
+            hidden,
             config,
         })
     }
@@ -158,9 +158,10 @@ impl SiteTree {
     /// Build a site by applying a given closure to each file.
     /// 
     /// # Parameters
-    /// - `convert` (`Fn(String, &Path, &Path, &Config, usize) -> BlogResult<String>`): the closure to
-    ///     apply to each source to construct each output, given a site root, a filename,
-    ///     a configuration structure, and a verbosity level
+    /// - `convert` (`Fn(String, &Path, &Path, &Config, usize) -> BlogResult<Option<String>>`): the
+    ///   closure to apply to each source to construct each output, given a site root, a filename,
+    ///   a configuration structure, and a verbosity level; it returns `None` for pages that should
+    ///   not be built
     /// - `verbosity` (`usize`): verbosity level of build
     /// 
     /// # Returns
@@ -170,13 +171,20 @@ impl SiteTree {
     /// # Errors
     /// This function returns an error if it was unable to perform any read/write
     ///     operations correctly.
-    pub fn build(&self, convert: impl Fn(String, &Path, &Path, &Config, usize) -> BlogResult<String>, verbosity: usize) -> BlogResult<Duration> {
+    pub fn build(&self, convert: impl Fn(String, &Path, &Path, &Config, usize) -> BlogResult<Option<String>>, verbosity: usize) -> BlogResult<Duration> {
         // Start a timer
         let start = Instant::now();
 
         // Initialize result
         let mut result: BlogResult<Duration> = BlogResult::default();
 
+        // Report files that are not built as pages, such as partials
+        if verbosity > 1 {
+            for file in &self.hidden {
+                println!("{:>12} hidden '/{}'", "Skipping".bright_yellow(), file.display());
+            }
+        }
+
         // Build each file
         for file in &self.files {
             // Print filename, if verbose
@@ -201,10 +209,19 @@ impl SiteTree {
             }
 
             // Convert the source into output
-            let output: String = unwrap_or_continue!(
+            let output: String = if let Some (o) = unwrap_or_continue!(
                 convert(source, &self.root, file, &self.config, verbosity),
                 result
-            );
+            ) {
+                o
+            } else {
+                // Skip drafts
+                if verbosity > 1 {
+                    println!("{:>12} draft '/{}'", "Skipping".bright_yellow(), file.display());
+                }
+
+                continue;
+            };
 
             // Construct the output file
             let output_file = self.output_directory.join(file).with_extension(OUTPUT_FILE_EXT);
@@ -327,3 +344,54 @@ impl SiteTree {
         let _ = fs::remove_dir_all(&self.output_directory);
     }
 }
+
+/// List the pages of a site.
+/// 
+/// # Parameters
+/// - `source_directory` (`&Path`): the source directory of the site
+/// 
+/// # Returns
+/// A `Vec<PathBuf>` containing the file stem of each page, relative
+/// to the source directory.
+pub fn pages(source_directory: &Path) -> Vec<PathBuf> {
+    sources(source_directory).into_iter()
+        .filter(|p| !is_hidden(p))
+        .map(|f| f.with_extension(""))
+        .collect()
+}
+
+/// List the source files of a site, including those that are not
+/// built as pages.
+/// 
+/// # Parameters
+/// - `source_directory` (`&Path`): the source directory of the site
+/// 
+/// # Returns
+/// A `Vec<PathBuf>` containing the path of each source file, relative
+/// to the source directory.
+fn sources(source_directory: &Path) -> Vec<PathBuf> {
+    // Construct a source file check closure
+    let is_source_file = |f: &DirEntry| f.path()
+        .extension()
+        .map(|osstr| osstr.to_str()) == Some (Some (SOURCE_FILE_EXT));
+
+    WalkDir::new(source_directory)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(is_source_file)
+        .map(|f| f.path().to_owned())
+        .filter_map(|p| pathdiff::diff_paths(p, source_directory))
+        .collect()
+}
+
+/// Check if a source file should not be built as a page.
+/// 
+/// # Parameters
+/// - `path` (`&Path`): the path of the file, relative to the source
+///   directory
+/// 
+/// # Returns
+/// A `bool` indicating if any component of the path begins with `_`.
+fn is_hidden(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str().to_string_lossy().starts_with('_'))
+}
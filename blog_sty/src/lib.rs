@@ -28,4 +28,7 @@ pub mod links {
 
     /// Linotype links.
     pub const LINOTYPE: &str = include_str!("../links/linotype.html");
+
+    /// MathJax, for equations not rendered at build time.
+    pub const MATHJAX: &str = include_str!("../links/mathjax.html");
 }
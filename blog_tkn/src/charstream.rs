@@ -1,6 +1,7 @@
 //! Character stream for the Blog Builder tokenizer.
 
 use crate::{
+    Span,
     Token,
     TokenClass,
 };
@@ -15,7 +16,8 @@ pub struct CharStream {
 
     /// Square bracket nesting depth.
     /// 
-    /// Text inside bracketed expressions ignore emphasis, etc.
+    /// Text inside bracketed expressions ignore everything except
+    /// emphasis.
     brackets: usize,
 
     /// Parenthesis nesting depth.
@@ -25,6 +27,22 @@ pub struct CharStream {
 
     /// Are we in an equation?
     equation: bool,
+
+    /// Number of dollar signs that opened the current equation, or
+    /// zero if it was not opened with dollar signs.
+    dollars: usize,
+
+    /// The current line number (starting from 1).
+    line: usize,
+
+    /// The current column number (starting from 1).
+    column: usize,
+
+    /// Was the previous token a blockquote marker?
+    quoted: bool,
+
+    /// Are we in a table row?
+    row: bool,
 }
 
 impl CharStream {
@@ -36,6 +54,19 @@ impl CharStream {
     /// # Returns
     /// A new `CharStream`.
     pub fn from(string: String) -> Self {
+        Self::from_line(string, 1)
+    }
+
+    /// Construct a new character stream from a string that begins
+    /// on a given line of its source file.
+    /// 
+    /// # Parameters
+    /// - `string` (`String`): the input string
+    /// - `line` (`usize`): the line number of the first line
+    /// 
+    /// # Returns
+    /// A new `CharStream`.
+    pub fn from_line(string: String, line: usize) -> Self {
         let chars = string.chars().collect::<Vec<char>>();
 
         Self {
@@ -44,6 +75,11 @@ impl CharStream {
             brackets: 0,
             parens: 0,
             equation: false,
+            dollars: 0,
+            line,
+            column: 1,
+            quoted: false,
+            row: false,
         }
     }
 
@@ -66,7 +102,7 @@ impl CharStream {
     /// 
     /// # Parameters
     /// - `chars` (`usize`): the number of characters to look ahead
-    ///     (zero is the next character).
+    ///   (zero is the next character).
     /// 
     /// # Returns
     /// An `Option<char>` containing the character, if it is
@@ -96,9 +132,477 @@ impl CharStream {
 
         self.index += 1;
 
+        // Keep track of our location in the source
+        match next {
+            Some ('\n') => {
+                self.line += 1;
+                self.column = 1;
+            },
+            Some (_) => self.column += 1,
+            None => (),
+        }
+
         next
     }
 
+    /// Check if a backslash followed by the character `chars` characters
+    /// ahead is an escape.
+    /// 
+    /// Any ASCII punctuation character may be escaped.  Nothing is
+    /// escaped inside of an equation, where backslashes begin LaTeX
+    /// commands.  Outside of an equation, `\[` and `\(` are escapes only
+    /// if they do not open an equation.
+    /// 
+    /// # Parameters
+    /// - `chars` (`usize`): the number of characters to look ahead to
+    ///   the character after the backslash
+    /// 
+    /// # Returns
+    /// A `bool` indicating if the character is escaped.
+    fn escapes(&self, chars: usize) -> bool {
+        if self.equation {
+            return false;
+        }
+
+        match self.look_ahead(chars) {
+            Some (c @ '[') | Some (c @ '(') => !self.opens(chars, c),
+            Some (c) => c.is_ascii_punctuation(),
+            None => false,
+        }
+    }
+
+    /// Check if a backslash followed by a bracket or parenthesis opens
+    /// an equation.
+    /// 
+    /// The bracket or parenthesis is escaped if it is closed by a plain
+    /// `]` or `)` before any `\]` or `\)`, as in `\[1]`.  Otherwise, it
+    /// opens an equation, even one that is never closed or is closed by
+    /// the wrong delimiter, so that the mistake is reported.
+    /// 
+    /// # Parameters
+    /// - `chars` (`usize`): the number of characters to look ahead to
+    ///   the bracket or parenthesis
+    /// - `open` (`char`): the bracket or parenthesis
+    /// 
+    /// # Returns
+    /// A `bool` indicating if an equation is opened.
+    fn opens(&self, chars: usize, open: char) -> bool {
+        let close = if open == '[' { ']' } else { ')' };
+
+        // Brackets and parentheses nested inside, as in `f(x)` or `[0, 1)`
+        let mut depth = 0;
+
+        let mut i = chars + 1;
+        while let Some (c) = self.look_ahead(i) {
+            match c {
+                '\\' if matches!(self.look_ahead(i + 1), Some (']') | Some (')')) => return true,
+
+                // Skip LaTeX control symbols, such as `\\`
+                '\\' => i += 1,
+
+                '[' | '(' => depth += 1,
+                ']' | ')' if depth > 0 => depth -= 1,
+                _ if c == close => return false,
+
+                '\n' if self.look_ahead(i + 1) == Some ('\n') => return true,
+                _ => (),
+            }
+
+            i += 1;
+        }
+
+        true
+    }
+
+    /// Check if the run of dollar signs at the next character delimits
+    /// an equation.
+    /// 
+    /// A single dollar sign opens an inline equation if it is followed
+    /// by a non-space character and closed on the same line by a dollar
+    /// sign that follows a non-space character and is not followed by a
+    /// digit, so that prices such as $5 are displayed as written.  Two
+    /// dollar signs open a display equation if they are closed by two
+    /// more before the next blank line.  Escaped dollar signs are never
+    /// delimiters.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// An `Option<usize>` containing the number of dollar signs in the
+    /// delimiter, if it is one.
+    fn dollars(&self) -> Option<usize> {
+        let mut n = 0;
+        while self.look_ahead(n) == Some ('$') {
+            n += 1;
+        }
+
+        // Close the equation that is open, if it was opened by dollar signs
+        if self.dollars != 0 {
+            return if n >= self.dollars && (self.dollars == 2 || self.closes(0)) {
+                Some (self.dollars)
+            } else {
+                None
+            };
+        }
+
+        // Dollar signs inside of other equations and URIs are just text
+        if n == 0 || n > 2 || self.equation || self.parens != 0 {
+            return None;
+        }
+
+        if n == 1 && self.look_ahead(1).is_none_or(|c| c.is_whitespace()) {
+            return None;
+        }
+
+        // Search for the closing delimiter
+        let mut i = n;
+        while let Some (c) = self.look_ahead(i) {
+            match c {
+                '\\' => i += 1,
+                '\n' if n == 1 || self.look_ahead(i + 1) == Some ('\n') => return None,
+                '$' if n == 1 && self.closes(i) => return Some (1),
+                '$' if self.look_ahead(i + 1) == Some ('$') => return Some (2),
+                _ => (),
+            }
+
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Check if a dollar sign may close an inline equation.
+    /// 
+    /// # Parameters
+    /// - `chars` (`usize`): the number of characters to look ahead to
+    ///   the dollar sign, which must not be the first character
+    /// 
+    /// # Returns
+    /// A `bool` indicating if the dollar sign follows a non-space
+    /// character and is not followed by a digit.
+    fn closes(&self, chars: usize) -> bool {
+        let index = self.index + chars;
+
+        !self.chars[index - 1].is_whitespace()
+            && !self.chars.get(index + 1).is_some_and(|c| c.is_ascii_digit())
+    }
+
+    /// Check if the run of underscores at the next character is inside
+    /// of a word.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `bool` indicating if the underscores are preceded and followed
+    /// by letters or digits.
+    fn intraword(&self) -> bool {
+        self.index > 0 && self.intraword_at(self.index)
+    }
+
+    /// Check if the run of underscores at a given index is inside
+    /// of a word.
+    /// 
+    /// # Parameters
+    /// - `index` (`usize`): the index of the first underscore, which
+    ///   must be greater than zero
+    /// 
+    /// # Returns
+    /// A `bool` indicating if the underscores are preceded and followed
+    /// by letters or digits.
+    fn intraword_at(&self, index: usize) -> bool {
+        let mut end = index;
+        while self.chars.get(end) == Some (&'_') {
+            end += 1;
+        }
+
+        self.chars[index - 1].is_alphanumeric()
+            && self.chars.get(end).is_some_and(|c| c.is_alphanumeric())
+    }
+
+    /// Read a fenced code block out of this character stream.
+    /// 
+    /// This function assumes that the first backtick of the opening
+    /// fence has already been consumed.  The contents of the block are
+    /// taken verbatim until a closing fence of at least the same length
+    /// is found at the start of a line, or until EOF.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `String` containing the info string, followed by a newline
+    /// and the contents of the block.
+    fn fence(&mut self) -> String {
+        // Measure the opening fence
+        let mut length = 1;
+        while self.peek() == Some ('`') {
+            length += 1;
+            self.next();
+        }
+
+        // Read the info string
+        let mut info = String::new();
+        while let Some (c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+
+            info.push(c);
+            self.next();
+        }
+
+        // Read each line until we find the closing fence
+        let mut lines: Vec<String> = Vec::new();
+        while self.next().is_some() {
+            // Get the next line
+            let mut line = String::new();
+            while let Some (c) = self.peek() {
+                if c == '\n' {
+                    break;
+                }
+
+                line.push(c);
+                self.next();
+            }
+
+            // Is this the closing fence?
+            let backticks = line.chars().take_while(|c| *c == '`').count();
+            if backticks >= length && line[backticks..].trim().is_empty() {
+                break;
+            }
+
+            lines.push(line);
+        }
+
+        format!("{}\n{}", info.trim(), lines.join("\n"))
+    }
+
+    /// Check if the rest of the line is the name of a block control
+    /// sequence.
+    /// 
+    /// # Parameters
+    /// - `name` (`&str`): the name of the control sequence
+    /// 
+    /// # Returns
+    /// A `bool` indicating if the rest of the line (ignoring trailing
+    /// whitespace) is `name`.
+    fn block(&self, name: &str) -> bool {
+        let mut i = 0;
+        for c in name.chars() {
+            if self.look_ahead(i) != Some (c) {
+                return false;
+            }
+
+            i += 1;
+        }
+
+        // Only whitespace may follow the name
+        while let Some (c) = self.look_ahead(i) {
+            if c == '\n' {
+                break;
+            } else if !c.is_whitespace() {
+                return false;
+            }
+
+            i += 1;
+        }
+
+        true
+    }
+
+    /// Read a raw block out of this character stream.
+    /// 
+    /// This function assumes that the opening line begins with a
+    /// block control sequence whose `::` has already been consumed.
+    /// The contents of the block are taken verbatim until a line
+    /// containing only `::end`, or until EOF.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `String` containing the contents of the block.
+    fn raw(&mut self) -> String {
+        // Skip the rest of the opening line
+        while let Some (c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+
+            self.next();
+        }
+
+        // Read each line until we find the end of the block
+        let mut lines: Vec<String> = Vec::new();
+        while self.next().is_some() {
+            // Get the next line
+            let mut line = String::new();
+            while let Some (c) = self.peek() {
+                if c == '\n' {
+                    break;
+                }
+
+                line.push(c);
+                self.next();
+            }
+
+            if line.trim() == "::end" {
+                break;
+            }
+
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+
+    /// Read an inline code span out of this character stream.
+    /// 
+    /// This function assumes that the first backtick of the opening
+    /// delimiter has already been consumed.  The code span is closed by
+    /// a run of backticks of the same length on the same line.  If there
+    /// is no such run, the character stream is not advanced.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// An `Option<String>` containing the verbatim contents of the code
+    /// span, if it is closed.
+    fn code(&mut self) -> Option<String> {
+        // Measure the opening delimiter
+        let mut length = 1;
+        while self.look_ahead(length - 1) == Some ('`') {
+            length += 1;
+        }
+
+        // Search the rest of the line for a closing delimiter
+        let start = length - 1;
+        let mut i = start;
+        let end = loop {
+            match self.look_ahead(i) {
+                Some ('`') => {
+                    // Measure this run of backticks
+                    let mut run = 0;
+                    while self.look_ahead(i + run) == Some ('`') {
+                        run += 1;
+                    }
+
+                    if run == length {
+                        break i;
+                    }
+
+                    i += run;
+                },
+                Some ('\n') | None => return None,
+                Some (_) => i += 1,
+            }
+        };
+
+        // Consume the opening delimiter, the code, and the closing delimiter
+        let mut code = String::new();
+        for j in 0..(end + length) {
+            let c = self.next()?;
+
+            if j >= start && j < end {
+                code.push(c);
+            }
+        }
+
+        // Strip a single space from each side, if present on both
+        if code.len() > 1 && code.starts_with(' ') && code.ends_with(' ') {
+            code = code[1..code.len() - 1].to_string();
+        }
+
+        Some (code)
+    }
+
+    /// Read a blockquote marker out of this character stream.
+    /// 
+    /// Each `>` may be followed by a single space, and nested markers
+    /// may be separated by spaces.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `String` containing the marker.
+    fn quote(&mut self) -> String {
+        let mut marker = String::new();
+
+        while let Some (c) = self.peek() {
+            if c == '>' {
+                marker.push(c);
+                self.next();
+
+                // Consume a single space after each `>`
+                if self.peek() == Some (' ') {
+                    self.next();
+                }
+            } else if c == ' ' && self.look_ahead(1) == Some ('>') {
+                // Skip spaces between nested markers
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        marker
+    }
+
+    /// Read a list item marker out of this character stream.
+    /// 
+    /// A list item marker is a hyphen or a number followed by a period
+    /// or a close parenthesis, possibly indented, and followed by a
+    /// space.  If there is no marker, the character stream is not
+    /// advanced.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// An `Option<String>` containing the marker, including its
+    /// indentation and the following space, if it is present.
+    fn marker(&mut self) -> Option<String> {
+        // Skip indentation
+        let mut i = 0;
+        while matches!(self.look_ahead(i), Some (' ') | Some ('\t')) {
+            i += 1;
+        }
+
+        // Check for a bullet or a number
+        match self.look_ahead(i) {
+            Some ('-') => i += 1,
+            Some (c) if c.is_ascii_digit() => {
+                while self.look_ahead(i).is_some_and(|c| c.is_ascii_digit()) {
+                    i += 1;
+                }
+
+                if !matches!(self.look_ahead(i), Some ('.') | Some (')')) {
+                    return None;
+                }
+
+                i += 1;
+            },
+            _ => return None,
+        }
+
+        // The marker must be followed by a space
+        if self.look_ahead(i) != Some (' ') {
+            return None;
+        }
+
+        // Consume the marker
+        let mut marker = String::new();
+        for _ in 0..=i {
+            marker.push(self.next()?);
+        }
+
+        Some (marker)
+    }
+
     /// Get the next token out of this character stream.
     /// 
     /// # Parameters
@@ -110,11 +614,92 @@ impl CharStream {
     pub fn get(&mut self) -> Option<Token> {
         use TokenClass::*;
 
+        // Record the location of the first character in the token
+        let start = self.index;
+        let span = Span {
+            line: self.line,
+            column: self.column,
+            length: 0,
+        };
+
+        // Text following a blockquote marker is treated as the start of a line
+        let quoted = std::mem::replace(&mut self.quoted, false);
+
+        // Blockquote and list item markers may only appear at the start of a line
+        if self.column == 1 && self.brackets == 0 && self.parens == 0 && self.peek() == Some ('>') {
+            self.quoted = true;
+
+            return Some (Token {
+                class: Quote,
+                value: self.quote(),
+                span: Span {
+                    length: self.index - start,
+                    ..span
+                },
+            });
+        }
+
+        // Table rows begin with a pipe, and pipes separate the cells of a row
+        if (self.row || self.column == 1 || quoted) && self.brackets == 0 && self.peek() == Some ('|') {
+            self.row = true;
+            self.next();
+
+            return Some (Token {
+                class: Pipe,
+                value: "|".to_string(),
+                span: Span {
+                    length: 1,
+                    ..span
+                },
+            });
+        }
+
+        if (self.column == 1 || quoted) && self.brackets == 0 && self.parens == 0 {
+            if let Some (value) = self.marker() {
+                return Some (Token {
+                    class: ListItem,
+                    value,
+                    span: Span {
+                        length: self.index - start,
+                        ..span
+                    },
+                });
+            }
+        }
+
+        // Dollar signs delimit equations, as in LaTeX
+        if let Some (n) = self.dollars() {
+            let opening = self.dollars == 0;
+            for _ in 0..n {
+                self.next();
+            }
+
+            self.equation = opening;
+            self.dollars = if opening { n } else { 0 };
+
+            // Equations are passed on with the same delimiters as `\(` and `\[`
+            let value = match (n, opening) {
+                (1, true) => "\\(",
+                (1, false) => "\\)",
+                (_, true) => "\\[",
+                (_, false) => "\\]",
+            };
+
+            return Some (Token {
+                class: Paragraph,
+                value: value.to_string(),
+                span: Span {
+                    length: n,
+                    ..span
+                },
+            });
+        }
+
         // Get the first character in the token
         let first: char = self.next()?;
 
         // Build the token
-        let token = match TokenClass::class(first) {
+        let mut token = match TokenClass::class(first) {
             Hashes => {
                 let mut value = String::new();
 
@@ -133,6 +718,21 @@ impl CharStream {
                 Token {
                     value,
                     class: Hashes,
+                    span,
+                }
+            },
+            Emphasis if first == '_' && self.index > 1 && self.intraword_at(self.index - 1) => {
+                // Underscores inside of words are just text
+                let mut value = String::from(first);
+                while self.peek() == Some ('_') {
+                    value.push('_');
+                    self.next();
+                }
+
+                Token {
+                    class: Paragraph,
+                    value,
+                    span,
                 }
             },
             Emphasis => if !self.equation {
@@ -153,11 +753,13 @@ impl CharStream {
                 Token {
                     value,
                     class: Emphasis,
+                    span,
                 }
             } else {
                 Token {
                     class: Paragraph,
                     value: first.to_string(),
+                    span,
                 }
             },
             Paragraph => {
@@ -170,10 +772,64 @@ impl CharStream {
                 // When inside brackets, ignore everything except
                 //  close brackets
                 while let Some (t) = self.peek() {
+                    // A blank line always ends the text, even inside of
+                    //  unclosed brackets or parentheses
+                    if t == '\n' && self.look_ahead(1) == Some ('\n') {
+                        break;
+                    }
+
+                    // Pipes separate the cells of a table row
+                    if self.row && t == '|' && self.brackets == 0 {
+                        break;
+                    }
+
+                    // Backslash escapes are separate tokens, even
+                    //  inside brackets
+                    if t == '\\' && self.parens == 0 && self.escapes(1) {
+                        break;
+                    }
+
+                    // Equations inside brackets are kept in the text,
+                    //  but still open and close, so that the closing
+                    //  delimiter is not taken for an escape
+                    if t == '\\' && self.brackets != 0 {
+                        if let Some (n @ ('[' | '(' | ']' | ')' | '\\')) = self.look_ahead(1) {
+                            match n {
+                                '[' | '(' => self.equation = true,
+                                ']' | ')' => self.equation = false,
+                                _ => (),
+                            }
+
+                            // Keep the backslash and the delimiter together
+                            value.push(t);
+                            value.push(n);
+                            self.next();
+                            self.next();
+                            continue;
+                        }
+                    }
+
+                    // Dollar signs may delimit equations
+                    if t == '$' && self.dollars().is_some() {
+                        break;
+                    }
+
+                    // Underscores inside of words are just text
+                    if t == '_' && self.intraword() {
+                        while self.peek() == Some ('_') {
+                            value.push('_');
+                            self.next();
+                        }
+
+                        continue;
+                    }
+
+                    // Emphasis may be used inside brackets (but not
+                    //  inside parentheses, which hold URIs)
                     let class = TokenClass::class(t);
                     if class == Paragraph
                         || class == Control
-                        || (self.brackets != 0 && class != CloseSquare)
+                        || (self.brackets != 0 && class != CloseSquare && (class != Emphasis || self.equation))
                         || (self.parens != 0 && class != CloseParen)
                     {
                         value.push(t);
@@ -186,11 +842,25 @@ impl CharStream {
                 Token {
                     value: value.to_string(),
                     class: Paragraph,
+                    span,
                 }
             },
-            Newline => Token {
-                class: Newline,
-                value: "\n".to_string(),
+            Newline => {
+                // Table rows end at the end of the line
+                self.row = false;
+
+                // Unclosed brackets and parentheses end at a blank line,
+                //  so that they don't swallow the rest of the file
+                if self.peek() == Some ('\n') {
+                    self.brackets = 0;
+                    self.parens = 0;
+                }
+
+                Token {
+                    class: Newline,
+                    value: "\n".to_string(),
+                    span,
+                }
             },
             OpenParen => {
                 // Increment nesting depth
@@ -199,6 +869,7 @@ impl CharStream {
                 Token {
                     class: OpenParen,
                     value: "(".to_string(),
+                    span,
                 }
             },
             CloseParen => {
@@ -211,6 +882,7 @@ impl CharStream {
                 Token {
                     class: CloseParen,
                     value: ")".to_string(),
+                    span,
                 }
             },
             OpenSquare => {
@@ -220,6 +892,7 @@ impl CharStream {
                 Token {
                     class: OpenSquare,
                     value: "[".to_string(),
+                    span,
                 }
             },
             CloseSquare => {
@@ -232,6 +905,7 @@ impl CharStream {
                 Token {
                     class: CloseSquare,
                     value: "]".to_string(),
+                    span,
                 }
             },
             Control => if let Some (t) = self.peek() {
@@ -239,22 +913,50 @@ impl CharStream {
                 if TokenClass::class(t) == Control {
                     // Consume the second colon
                     let _ = self.next();
-                    Token {
-                        class: Control,
-                        value: "::".to_string(),
+
+                    if span.column == 1 && self.block("html") {
+                        // Raw HTML blocks are taken verbatim
+                        Token {
+                            class: RawHtml,
+                            value: self.raw(),
+                            span,
+                        }
+                    } else {
+                        Token {
+                            class: Control,
+                            value: "::".to_string(),
+                            span,
+                        }
                     }
                 } else {
-                    // We didn't get our second colon
-                    return None;
+                    // We didn't get our second colon, so this is just text
+                    Token {
+                        class: Paragraph,
+                        value: ":".to_string(),
+                        span,
+                    }
                 }
             } else {
-                // Unexpected EOF
-                return None;
+                // A colon at EOF is just text
+                Token {
+                    class: Paragraph,
+                    value: ":".to_string(),
+                    span,
+                }
             },
             Backslash => if let Some (t) = self.peek() {
-                // Check for a bracket or parenthesis (this indicates an equation)
-                if TokenClass::class(t) == OpenSquare {
-                    // Consume the bracket
+                if self.escapes(0) {
+                    // Consume the escaped character, which may be a bracket
+                    //  or parenthesis that doesn't delimit an equation
+                    let _ = self.next();
+
+                    Token {
+                        class: Paragraph,
+                        value: t.to_string(),
+                        span,
+                    }
+                } else if TokenClass::class(t) == OpenSquare {
+                    // Consume the bracket (this indicates an equation)
                     let _ = self.next();
 
                     // We're now in an equation
@@ -263,6 +965,7 @@ impl CharStream {
                     Token {
                         class: Paragraph,
                         value: "\\[".to_string(),
+                        span,
                     }
                 } else if TokenClass::class(t) == CloseSquare {
                     // Consume the bracket
@@ -274,6 +977,7 @@ impl CharStream {
                     Token {
                         class: Paragraph,
                         value: "\\]".to_string(),
+                        span,
                     }
                 } else if TokenClass::class(t) == OpenParen {
                     // Consume the parenthesis
@@ -285,6 +989,7 @@ impl CharStream {
                     Token {
                         class: Paragraph,
                         value: "\\(".to_string(),
+                        span,
                     }
                 } else if TokenClass::class(t) == CloseParen {
                     // Consume the parenthesis
@@ -296,12 +1001,23 @@ impl CharStream {
                     Token {
                         class: Paragraph,
                         value: "\\)".to_string(),
+                        span,
+                    }
+                } else if self.equation && t.is_ascii_punctuation() {
+                    // Keep LaTeX control symbols (such as `\#`) intact
+                    let _ = self.next();
+
+                    Token {
+                        class: Paragraph,
+                        value: format!("\\{}", t),
+                        span,
                     }
                 } else {
                     // We didn't get our bracket
                     Token {
                         class: Paragraph,
                         value: "\\".to_string(),
+                        span,
                     }
                 }
             } else {
@@ -311,9 +1027,75 @@ impl CharStream {
             Menu => Token {
                 class: Menu,
                 value: "~".to_string(),
+                span,
             },
+            Backtick => if span.column == 1 && self.peek() == Some ('`') && self.look_ahead(1) == Some ('`') {
+                // Three backticks at the start of a line open a fenced code block
+                Token {
+                    class: CodeBlock,
+                    value: self.fence(),
+                    span,
+                }
+            } else if let Some (code) = self.code() {
+                Token {
+                    class: Backtick,
+                    value: code,
+                    span,
+                }
+            } else {
+                // No closing backticks, so this is just text
+                let mut value = first.to_string();
+                while self.peek() == Some ('`') {
+                    value.push('`');
+                    self.next();
+                }
+
+                Token {
+                    class: Paragraph,
+                    value,
+                    span,
+                }
+            },
+            ListItem | Quote | Pipe | CodeBlock | RawHtml => unreachable!(),
         };
 
+        // Record the length of the token
+        token.span.length = self.index - start;
+
         Some (token)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::{
+        TokenClass,
+        Tokenizer,
+    };
+
+    /// Get the text of every paragraph token in a source string.
+    fn text(source: &str) -> String {
+        let mut tokenizer = Tokenizer::from(source.to_owned());
+        let mut output = String::new();
+
+        while let Some (t) = tokenizer.next() {
+            if t.class == TokenClass::Paragraph {
+                output.push_str(&t.value);
+            }
+        }
+
+        output
+    }
+
+    #[test]
+    fn equation_inside_brackets_keeps_delimiters() {
+        let sources = [
+            ("::notice[See \\( x \\) here]\n", "See \\( x \\) here"),
+            ("::mathblock[Theorem][Euler \\(e^{i\\pi}\\)]\n", "Euler \\(e^{i\\pi}\\)"),
+            ("[see \\(x\\)](/u)\n", "see \\(x\\)"),
+        ];
+
+        for (source, expected) in sources {
+            assert!(text(source).contains(expected), "{}", text(source));
+        }
+    }
+}
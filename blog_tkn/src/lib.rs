@@ -7,10 +7,13 @@
 #![deny(missing_docs)]
 
 mod charstream;
+mod span;
 mod token;
 
 pub use charstream::CharStream;
 
+pub use span::Span;
+
 pub use token::{
     TokenClass,
     Token,
@@ -36,8 +39,21 @@ impl Tokenizer {
     /// # Returns
     /// A `Tokenizer` containing the full token stream.
     pub fn from(string: String) -> Self {
+        Self::from_line(string, 1)
+    }
+
+    /// Construct a new tokenizer from an input string that begins
+    /// on a given line of its source file.
+    /// 
+    /// # Parameters
+    /// - `string` (`String`): a string input
+    /// - `line` (`usize`): the line number of the first line
+    /// 
+    /// # Returns
+    /// A `Tokenizer` containing the full token stream.
+    pub fn from_line(string: String, line: usize) -> Self {
         // Construct a character stream
-        let mut charstream = CharStream::from(string);
+        let mut charstream = CharStream::from_line(string, line);
 
         // Initialize a list of tokens
         let mut tokens = Vec::new();
@@ -82,10 +98,78 @@ impl Tokenizer {
     /// **Note**: this function does not advance the token
     /// stream.
     pub fn peek(&self) -> Option<Token> {
-        if self.index < self.tokens.len() {
-            Some (self.tokens[self.index].to_owned())
+        self.look_ahead(0)
+    }
+
+    /// Look ahead `tokens` tokens in the stream.
+    /// 
+    /// # Parameters
+    /// - `tokens` (`usize`): the number of tokens to look ahead
+    ///   (zero is the next token)
+    /// 
+    /// # Returns
+    /// An `Option<Token>` containing the token, if it is available.
+    /// 
+    /// **Note**: this function does not advance the token
+    /// stream.
+    pub fn look_ahead(&self, tokens: usize) -> Option<Token> {
+        self.tokens.get(self.index + tokens).cloned()
+    }
+
+    /// Get the location of the most recently consumed token.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `Span` containing the location of the last token taken
+    /// out of the stream, or the location of the final token if the
+    /// stream has been exhausted.
+    pub fn span(&self) -> Span {
+        let index = self.index.min(self.tokens.len());
+
+        if index > 0 {
+            self.tokens[index - 1].span
         } else {
-            None
+            Span::default()
+        }
+    }
+
+    /// Check if the most recently consumed token was a newline.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// A `bool` indicating whether the stream is at the start of a line.
+    pub fn at_line_start(&self) -> bool {
+        self.index > 0
+            && self.tokens.get(self.index - 1).map(|t| t.class) == Some (TokenClass::Newline)
+    }
+
+    /// Skip tokens until reaching a point where parsing can safely
+    /// resume after an error.
+    /// 
+    /// Parsing resumes at the start of the next line or at the next
+    /// control sequence, whichever comes first.
+    /// 
+    /// # Parameters
+    /// None.
+    /// 
+    /// # Returns
+    /// None.
+    pub fn synchronize(&mut self) {
+        // If we just consumed a newline, we're already at the start of a line
+        if self.at_line_start() {
+            return;
+        }
+
+        while let Some (t) = self.peek() {
+            if t.class == TokenClass::Newline || t.class == TokenClass::Control {
+                break;
+            }
+
+            self.next();
         }
     }
 
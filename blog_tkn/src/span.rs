@@ -0,0 +1,47 @@
+//! Source locations for the Blog Builder tokenizer.
+
+use std::fmt::{
+    self,
+    Display,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+/// Location of a token in a source file.
+pub struct Span {
+    /// Line number (starting from 1).
+    pub line: usize,
+
+    /// Column number (starting from 1).
+    pub column: usize,
+
+    /// Length of the token in characters.
+    pub length: usize,
+}
+
+impl Span {
+    /// Extend this span to the end of a later span on the same line.
+    /// 
+    /// # Parameters
+    /// - `end` (`Span`): the later span
+    /// 
+    /// # Returns
+    /// A `Span` from the start of this span to the end of `end`, or
+    /// this span if `end` is on another line.
+    pub fn to(self, end: Span) -> Span {
+        if end.line != self.line || end.column < self.column {
+            return self;
+        }
+
+        Span {
+            length: end.column + end.length - self.column,
+            ..self
+        }
+    }
+}
+
+// Used for error handling.
+impl Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -1,5 +1,7 @@
 //! Token enumeration for the Blog Builder.
 
+use crate::Span;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 /// Types of tokens available to the Blog Builder.
 pub enum TokenClass {
@@ -35,6 +37,40 @@ pub enum TokenClass {
 
     /// Tilde (for menu).
     Menu,
+
+    /// Backtick (for code).
+    /// 
+    /// The value of this token is the verbatim contents of an
+    /// inline code span.
+    Backtick,
+
+    /// List item marker.
+    /// 
+    /// The value of this token is the marker, including any
+    /// indentation before it.
+    ListItem,
+
+    /// Blockquote marker.
+    /// 
+    /// The value of this token is the full sequence of `>` characters
+    /// at the start of the line, one for each level of nesting.
+    Quote,
+
+    /// Pipe (for tables).
+    /// 
+    /// Pipes are only recognized in lines that begin with a pipe.
+    Pipe,
+
+    /// Fenced code block.
+    /// 
+    /// The value of this token is the info string, followed by a
+    /// newline and the verbatim contents of the block.
+    CodeBlock,
+
+    /// Raw HTML block.
+    /// 
+    /// The value of this token is the verbatim contents of the block.
+    RawHtml,
 }
 
 impl TokenClass {
@@ -59,6 +95,12 @@ impl TokenClass {
             Control => "Control",
             Backslash => "Backslash",
             Menu => "Menu",
+            Backtick => "Backtick",
+            ListItem => "ListItem",
+            Quote => "Quote",
+            Pipe => "Pipe",
+            CodeBlock => "CodeBlock",
+            RawHtml => "RawHtml",
         };
 
         string.to_string()
@@ -85,6 +127,7 @@ impl TokenClass {
             '*' | '_' => Emphasis,
             ':' => Control,
             '\\' => Backslash,
+            '`' => Backtick,
             _ => Paragraph,
         }
     }
@@ -98,4 +141,7 @@ pub struct Token {
 
     /// Token class.
     pub class: TokenClass,
+
+    /// Token location.
+    pub span: Span,
 }
\ No newline at end of file
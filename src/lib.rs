@@ -23,7 +23,10 @@ pub mod cfg {
 
 /// Source-to-output conversion.
 pub mod cvt {
-    pub use blog_cvt::convert;
+    pub use blog_cvt::{
+        Converter,
+        convert,
+    };
 }
 
 /// User help utility.
@@ -36,10 +39,37 @@ pub mod err {
     pub use blog_err::{
         BlogError,
         BlogResult,
+        render,
         unwrap_or_return,
     };
 }
 
+/// Parsing, for extensions.
+pub mod prs {
+    pub use blog_prs::{
+        Control,
+        CustomControl,
+        Expression,
+        Inline,
+        ParseError,
+        Parselet,
+        Parser,
+        escape,
+        escape_uri,
+        parselets,
+    };
+}
+
+/// Tokenization, for extensions.
+pub mod tkn {
+    pub use blog_tkn::{
+        Span,
+        Token,
+        TokenClass,
+        Tokenizer,
+    };
+}
+
 /// Website tree management.
 pub mod site {
     pub use blog_str::SiteTree;
@@ -5,6 +5,7 @@ use std::process::exit;
 use blog::{
     err::{
         BlogResult,
+        render,
         unwrap_or_return,
     },
     cli::{
@@ -12,7 +13,7 @@ use blog::{
         Subcommand,
     },
     site::SiteTree,
-    cvt::convert,
+    cvt::Converter,
     help::help,
     version::VERSION,
 };
@@ -26,7 +27,7 @@ fn main() {
         BlogResult::Err (e) => {
             // Print the errors
             for err in &e {
-                println!("\n{:>12} {}", "Error".bold().bright_red(), err);
+                println!("\n{}", render(err));
             }
 
             // Exit
@@ -65,10 +66,14 @@ fn run() -> BlogResult<()> {
 
             // Unwrap site tree
             let sitetree = unwrap_or_return!(sitetree);
+
+            // Share one converter between pages, so that cross-references
+            //  between pages are only looked up once
+            let converter = Converter::new();
             
             // Handle errors or clean
             let duration = unwrap_or_return!(
-                sitetree.build(convert, cli.verbosity),
+                sitetree.build(|s, r, f, c, v| converter.convert(s, r, f, c, v), cli.verbosity),
                 sitetree.clean()
             );
 